@@ -0,0 +1,157 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use util::Input;
+use util::input::LineError;
+
+use {fmt_time, solution};
+
+// One line of an answers file: `day part input expected...`, where the
+// input path is relative to the answers file and the expected answer
+// is the rest of the line.  Blank lines and `#` comments are skipped.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Case {
+    pub day: u32,
+    pub part: u32,
+    pub input: PathBuf,
+    pub expected: String,
+}
+
+// Splits off the first whitespace-delimited word, if there is one.
+fn next_word(s: &str) -> Option<(&str, &str)> {
+    let s = s.trim_start();
+    if s.is_empty() {
+        return None;
+    }
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    Some((&s[..end], &s[end..]))
+}
+
+pub fn parse_answers(input: Input, base: &Path) -> Result<Vec<Case>, LineError> {
+    let mut cases = vec![];
+    for line in input.lines() {
+        let line = line?;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut rest: &str = &line;
+        let mut field = |what| match next_word(rest) {
+            Some((word, tail)) => { rest = tail; Ok(word) }
+            None => Err(line.error(format!("missing {}", what))),
+        };
+        let (day, part, path) = (field("day")?, field("part")?, field("input path")?);
+        let expected = rest.trim();
+        if expected.is_empty() {
+            return Err(line.error("missing expected answer"));
+        }
+        cases.push(Case {
+            day: day.parse().map_err(|_| line.error_at(day, "bad day number"))?,
+            part: part.parse().map_err(|_| line.error_at(part, "bad part number"))?,
+            input: base.join(path),
+            expected: expected.to_owned(),
+        });
+    }
+    Ok(cases)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    Error(String),
+    Skip(String),
+}
+
+fn run_case(case: &Case) -> Outcome {
+    let soln = match solution(case.day) {
+        Some(soln) => soln,
+        None => return Outcome::Skip("not in this build".to_owned()),
+    };
+    let text = match Input::open(&case.input).map_err(|e| e.to_string())
+        .and_then(|input| input.read_text().map_err(|e| e.to_string())) {
+        Ok(text) => text,
+        Err(msg) => return Outcome::Error(format!("{}: {}", case.input.display(), msg)),
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| soln.part(case.part, &text))) {
+        Ok(Ok(ref got)) if *got == case.expected => Outcome::Pass,
+        Ok(Ok(got)) => Outcome::Fail(format!("got {}, expected {}", got, case.expected)),
+        Ok(Err(e)) => Outcome::Error(e.to_string()),
+        Err(payload) => {
+            let msg = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "?".to_owned());
+            Outcome::Error(format!("panicked: {}", msg))
+        }
+    }
+}
+
+// Runs everything and prints a table; true if nothing failed.
+pub fn check(cases: &[Case]) -> bool {
+    let mut counts = [0; 4];
+    // Panics are reported in the table, so don't also spew them.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| ()));
+    println!("day part  result        time  notes");
+    for case in cases {
+        let start = Instant::now();
+        let outcome = run_case(case);
+        let time = fmt_time(start.elapsed());
+        let (i, result, notes) = match outcome {
+            Outcome::Pass => (0, "pass", String::new()),
+            Outcome::Fail(s) => (1, "FAIL", s),
+            Outcome::Error(s) => (2, "ERROR", s),
+            Outcome::Skip(s) => (3, "skip", s),
+        };
+        counts[i] += 1;
+        let row = format!("{:>3} {:>4}  {:<6} {:>12}  {}", case.day, case.part, result, time, notes);
+        println!("{}", row.trim_end());
+    }
+    panic::set_hook(hook);
+    println!("{} passed, {} failed, {} errors, {} skipped",
+             counts[0], counts[1], counts[2], counts[3]);
+    counts[1] == 0 && counts[2] == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use util::Input;
+    use super::{parse_answers, run_case, Case, Outcome};
+
+    #[test]
+    fn parse() {
+        let text = "# day part input expected\n\
+                    \n\
+                    1 2 day01.txt 5\n\
+                    11  1  in/day11.txt   abcdffaa\n\
+                    21 1 x.txt no loadout does that\n";
+        let cases = parse_answers(Input::text(text), Path::new("base")).unwrap();
+        assert_eq!(cases, vec![
+            Case { day: 1, part: 2, input: PathBuf::from("base/day01.txt"),
+                   expected: "5".to_owned() },
+            Case { day: 11, part: 1, input: PathBuf::from("base/in/day11.txt"),
+                   expected: "abcdffaa".to_owned() },
+            Case { day: 21, part: 1, input: PathBuf::from("base/x.txt"),
+                   expected: "no loadout does that".to_owned() },
+        ]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = parse_answers(Input::text("1 2 day01.txt\n"), Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: missing expected answer");
+        let err = parse_answers(Input::text("\n1 two day01.txt 5\n"), Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 3: bad part number");
+    }
+
+    #[test]
+    fn missing_input() {
+        let case = Case { day: 1, part: 1, input: PathBuf::from("/nonexistent/day01.txt"),
+                          expected: "0".to_owned() };
+        match run_case(&case) {
+            Outcome::Error(ref msg) if msg.starts_with("/nonexistent/day01.txt: ") => (),
+            Outcome::Skip(_) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+#[cfg(feature = "day1")] extern crate day1;
+#[cfg(feature = "day2")] extern crate day2;
+#[cfg(feature = "day3")] extern crate day3;
+#[cfg(feature = "day4")] extern crate day4;
+#[cfg(feature = "day5")] extern crate day5;
+#[cfg(feature = "day6")] extern crate day6;
+#[cfg(feature = "day7")] extern crate day7;
+#[cfg(feature = "day8")] extern crate day8;
+#[cfg(feature = "day9")] extern crate day9;
+#[cfg(feature = "day10")] extern crate day10;
+#[cfg(feature = "day11")] extern crate day11;
+#[cfg(feature = "day12")] extern crate day12;
+#[cfg(feature = "day13")] extern crate day13;
+#[cfg(feature = "day14")] extern crate day14;
+#[cfg(feature = "day15")] extern crate day15;
+#[cfg(feature = "day16")] extern crate day16;
+#[cfg(feature = "day17")] extern crate day17;
+#[cfg(feature = "day18")] extern crate day18;
+#[cfg(feature = "day19")] extern crate day19;
+#[cfg(feature = "day20")] extern crate day20;
+#[cfg(feature = "day21")] extern crate day21;
+#[cfg(feature = "day22")] extern crate day22;
+#[cfg(feature = "day23")] extern crate day23;
+#[cfg(feature = "day24")] extern crate day24;
+#[cfg(feature = "day25")] extern crate day25;
+extern crate util;
+
+mod check;
+
+use std::env;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
+use util::Input;
+use util::solution::{json_report, Solution};
+
+// Days left out of the build (see the features in Cargo.toml) are just
+// missing here.
+fn solution(day: u32) -> Option<&'static dyn Solution> {
+    match day {
+        #[cfg(feature = "day1")] 1 => Some(&day1::Day),
+        #[cfg(feature = "day2")] 2 => Some(&day2::Day),
+        #[cfg(feature = "day3")] 3 => Some(&day3::Day),
+        #[cfg(feature = "day4")] 4 => Some(&day4::Day),
+        #[cfg(feature = "day5")] 5 => Some(&day5::Day),
+        #[cfg(feature = "day6")] 6 => Some(&day6::Day),
+        #[cfg(feature = "day7")] 7 => Some(&day7::Day),
+        #[cfg(feature = "day8")] 8 => Some(&day8::Day),
+        #[cfg(feature = "day9")] 9 => Some(&day9::Day),
+        #[cfg(feature = "day10")] 10 => Some(&day10::Day),
+        #[cfg(feature = "day11")] 11 => Some(&day11::Day),
+        #[cfg(feature = "day12")] 12 => Some(&day12::Day),
+        #[cfg(feature = "day13")] 13 => Some(&day13::Day),
+        #[cfg(feature = "day14")] 14 => Some(&day14::Day),
+        #[cfg(feature = "day15")] 15 => Some(&day15::Day),
+        #[cfg(feature = "day16")] 16 => Some(&day16::Day),
+        #[cfg(feature = "day17")] 17 => Some(&day17::Day),
+        #[cfg(feature = "day18")] 18 => Some(&day18::Day),
+        #[cfg(feature = "day19")] 19 => Some(&day19::Day),
+        #[cfg(feature = "day20")] 20 => Some(&day20::Day),
+        #[cfg(feature = "day21")] 21 => Some(&day21::Day),
+        #[cfg(feature = "day22")] 22 => Some(&day22::Day),
+        #[cfg(feature = "day23")] 23 => Some(&day23::Day),
+        #[cfg(feature = "day24")] 24 => Some(&day24::Day),
+        #[cfg(feature = "day25")] 25 => Some(&day25::Day),
+        _ => None,
+    }
+}
+
+const USAGE: &str = "usage: aoc <day> [--part <1|2>] [--json] [<input file>|-]\n       \
+                     aoc check <answers file>";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    day: u32,
+    parts: Vec<u32>,
+    json: bool,
+    input: Option<String>,
+}
+
+fn parse_args<I: Iterator<Item=String>>(mut i: I) -> Result<Args, String> {
+    let mut day = None;
+    let mut parts = vec![1, 2];
+    let mut json = false;
+    let mut input = None;
+    while let Some(arg) = i.next() {
+        if arg == "--part" || arg == "-p" {
+            let part = i.next().ok_or_else(|| format!("{} needs an argument", arg))?;
+            match part.parse() {
+                Ok(n @ 1..=2) => parts = vec![n],
+                _ => return Err(format!("bad part number {:?}", part)),
+            }
+        } else if arg == "--json" {
+            json = true;
+        } else if arg.starts_with('-') && arg != "-" {
+            return Err(format!("unknown option {:?}", arg));
+        } else if day.is_none() {
+            day = Some(arg.parse().map_err(|_| format!("bad day number {:?}", arg))?);
+        } else if input.is_none() {
+            input = Some(arg);
+        } else {
+            return Err(format!("unexpected argument {:?}", arg));
+        }
+    }
+    Ok(Args { day: day.ok_or("no day given")?, parts, json, input })
+}
+
+fn fmt_time(d: Duration) -> String {
+    format!("{:.3} ms", d.as_secs() as f64 * 1e3 + d.subsec_nanos() as f64 / 1e6)
+}
+
+fn run_check(path: &str) -> ! {
+    let base = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+    let cases = Input::open(path).map_err(|e| e.to_string())
+        .and_then(|input| check::parse_answers(input, base).map_err(|e| e.to_string()))
+        .unwrap_or_else(|msg| {
+            eprintln!("aoc: {}: {}", path, msg);
+            process::exit(2);
+        });
+    process::exit(if check::check(&cases) { 0 } else { 1 });
+}
+
+fn main() {
+    let mut argv = env::args().skip(1).peekable();
+    if argv.peek().map(|a| a == "check") == Some(true) {
+        let argv: Vec<_> = argv.skip(1).collect();
+        if argv.len() != 1 {
+            eprintln!("aoc: check takes one answers file\n{}", USAGE);
+            process::exit(2);
+        }
+        run_check(&argv[0]);
+    }
+    let args = parse_args(argv).unwrap_or_else(|msg| {
+        eprintln!("aoc: {}\n{}", msg, USAGE);
+        process::exit(2);
+    });
+    let soln = solution(args.day).unwrap_or_else(|| {
+        eprintln!("aoc: no solution for day {} in this build", args.day);
+        process::exit(2);
+    });
+    let text = Input::from_arg(args.input.as_ref()).map_err(|e| e.to_string())
+        .and_then(|input| input.read_text().map_err(|e| e.to_string()))
+        .unwrap_or_else(|msg| {
+            eprintln!("aoc: can't read input: {}", msg);
+            process::exit(1);
+        });
+    if args.json {
+        let (json, ok) = json_report(args.day, soln, &args.parts, &text);
+        println!("{}", json);
+        process::exit(if ok { 0 } else { 1 });
+    }
+    let mut failed = false;
+    for &part in &args.parts {
+        let start = Instant::now();
+        let answer = soln.part(part, &text);
+        let time = fmt_time(start.elapsed());
+        match answer {
+            Ok(answer) => println!("Day {}, part {}: {}  ({})", args.day, part, answer, time),
+            Err(e) => {
+                println!("Day {}, part {}: error: {}  ({})", args.day, part, e, time);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, Args};
+
+    fn args(s: &str) -> Result<Args, String> {
+        parse_args(s.split_whitespace().map(|s| s.to_owned()))
+    }
+
+    #[test]
+    fn good() {
+        assert_eq!(args("7"), Ok(Args { day: 7, parts: vec![1, 2], json: false, input: None }));
+        assert_eq!(args("7 --part 2 input.txt"),
+                   Ok(Args { day: 7, parts: vec![2], json: false,
+                             input: Some("input.txt".to_owned()) }));
+        assert_eq!(args("-p 1 --json 25 -"),
+                   Ok(Args { day: 25, parts: vec![1], json: true, input: Some("-".to_owned()) }));
+    }
+
+    #[test]
+    fn bad() {
+        assert!(args("").is_err());
+        assert!(args("seven").is_err());
+        assert!(args("7 --part").is_err());
+        assert!(args("7 --part 3").is_err());
+        assert!(args("7 --verbose").is_err());
+        assert!(args("7 a b").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use generic::{Expr,ExprMap};
 
 pub type Signal = u16;
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Gate<Ident> {
     Imm(Signal),
     Not(Ident),
@@ -9,6 +9,17 @@ pub enum Gate<Ident> {
     Or(Ident, Ident),
     LShift(Ident, Shift),
     RShift(Ident, Shift),
+    Xor(Ident, Ident),
+    // Both of these wrap around.
+    Add(Ident, Ident),
+    Sub(Ident, Ident),
+    // The second input if the first is nonzero, else the third.  All
+    // three are evaluated either way, so every strategy agrees on what's
+    // a cycle, and so does `opt`.
+    Mux(Ident, Ident, Ident),
+    // By the second input's value; 16 or more shifts everything out.
+    LShiftBy(Ident, Ident),
+    RShiftBy(Ident, Ident),
 }
 impl<Ident> Expr for Gate<Ident> {
     type Ident = Ident;
@@ -22,8 +33,35 @@ impl<Ident> Expr for Gate<Ident> {
             Gate::Or(ref id0, ref id1) => try!(env(id0)) | try!(env(id1)),
             Gate::LShift(ref id, Shift(sh)) => try!(env(id)) << sh,
             Gate::RShift(ref id, Shift(sh)) => try!(env(id)) >> sh,
+            Gate::Xor(ref id0, ref id1) => env(id0)? ^ env(id1)?,
+            Gate::Add(ref id0, ref id1) => env(id0)?.wrapping_add(env(id1)?),
+            Gate::Sub(ref id0, ref id1) => env(id0)?.wrapping_sub(env(id1)?),
+            Gate::Mux(ref sel, ref id0, ref id1) => {
+                let (sel, val0, val1) = (env(sel)?, env(id0)?, env(id1)?);
+                if sel != 0 { val0 } else { val1 }
+            },
+            Gate::LShiftBy(ref id, ref sh) => {
+                let (val, sh) = (env(id)?, env(sh)?);
+                val.checked_shl(sh.into()).unwrap_or(0)
+            },
+            Gate::RShiftBy(ref id, ref sh) => {
+                let (val, sh) = (env(id)?, env(sh)?);
+                val.checked_shr(sh.into()).unwrap_or(0)
+            },
         })
     }
+    fn deps<F>(&self, mut f: F) where F: FnMut(&Self::Ident) {
+        match *self {
+            Gate::Imm(_) => (),
+            Gate::Not(ref id) => f(id),
+            Gate::And(ref id0, ref id1) | Gate::Or(ref id0, ref id1) |
+            Gate::Xor(ref id0, ref id1) | Gate::Add(ref id0, ref id1) |
+            Gate::Sub(ref id0, ref id1) | Gate::LShiftBy(ref id0, ref id1) |
+            Gate::RShiftBy(ref id0, ref id1) => { f(id0); f(id1) },
+            Gate::LShift(ref id, _) | Gate::RShift(ref id, _) => f(id),
+            Gate::Mux(ref sel, ref id0, ref id1) => { f(sel); f(id0); f(id1) },
+        }
+    }
 }
 impl<Ident, AltId> ExprMap<AltId> for Gate<Ident> {
     type AltExpr = Gate<AltId>;
@@ -36,11 +74,17 @@ impl<Ident, AltId> ExprMap<AltId> for Gate<Ident> {
             Gate::Or(ref id0, ref id1) => Gate::Or(f(id0), f(id1)),
             Gate::LShift(ref id, sh) => Gate::LShift(f(id), sh),
             Gate::RShift(ref id, sh) => Gate::RShift(f(id), sh),
+            Gate::Xor(ref id0, ref id1) => Gate::Xor(f(id0), f(id1)),
+            Gate::Add(ref id0, ref id1) => Gate::Add(f(id0), f(id1)),
+            Gate::Sub(ref id0, ref id1) => Gate::Sub(f(id0), f(id1)),
+            Gate::Mux(ref sel, ref id0, ref id1) => Gate::Mux(f(sel), f(id0), f(id1)),
+            Gate::LShiftBy(ref id, ref sh) => Gate::LShiftBy(f(id), f(sh)),
+            Gate::RShiftBy(ref id, ref sh) => Gate::RShiftBy(f(id), f(sh)),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Shift(u8);
 impl Shift {
     pub fn new(sh: u8) -> Option<Shift> { if sh < 16 { Some(Shift(sh)) } else { None } }
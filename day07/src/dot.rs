@@ -0,0 +1,180 @@
+use ast::{Gate,Signal};
+use generic::{Decl,Expr,ProgramT};
+use shells::Prog;
+use std::fmt::Write;
+
+// The parser's names for literals; see `parse`.
+const LIT_PREFIX: &str = " __lit";
+
+// A Graphviz rendering of `prog`: a node per wire, labelled with its
+// name and gate, and an edge from each wire it uses, labelled with what
+// that input's for if the order matters.  Literals don't get nodes of
+// their own; their values go on the gate instead, with what they're for
+// in the same way.  The entries are
+// drawn in bold, and if there are `signals` (one per wire, like from
+// `shells::signals`) the ones that have a value show it.
+pub fn dot(prog: &Prog, signals: Option<&[Option<Signal>]>) -> String {
+    let decls: Vec<Decl> = Decl::all(prog.len()).collect();
+    let is_entry = |d: Decl| prog.entries().contains(&d);
+    let literal = |d: Decl| match *prog.lookup(d) {
+        Gate::Imm(value) if prog.debug(d).starts_with(LIT_PREFIX) && !is_entry(d) => Some(value),
+        _ => None,
+    };
+    let mut out = String::new();
+    writeln!(out, "digraph circuit {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=box];").unwrap();
+    for &d in &decls {
+        if literal(d).is_some() {
+            continue;
+        }
+        let gate = prog.lookup(d);
+        let roles = roles(gate);
+        let mut wires = Vec::new();
+        let mut lits = Vec::new();
+        let mut k = 0;
+        gate.deps(|&dd| {
+            let role = roles.get(k).cloned().unwrap_or(None);
+            k += 1;
+            match literal(dd) {
+                Some(value) => lits.push(match role {
+                    Some(role) => format!("{} {}", role, value),
+                    None => value.to_string(),
+                }),
+                // `x -> y` is `x OR x`; once is enough.
+                None if !wires.iter().any(|&(w, _)| w == dd) => wires.push((dd, role)),
+                None => (),
+            }
+        });
+        let mut label = prog.debug(d);
+        let op = op(gate);
+        if !op.is_empty() {
+            label.push('\n');
+            label.push_str(&op);
+        }
+        if !lits.is_empty() {
+            label.push(' ');
+            label.push_str(&lits.join(" "));
+        }
+        if let Some(&Some(value)) = signals.and_then(|s| s.get(d.get())) {
+            label.push_str(&format!("\n= {}", value));
+        }
+        let bold = if is_entry(d) { ", style=bold" } else { "" };
+        writeln!(out, "    w{} [label={:?}{}];", d.get(), label, bold).unwrap();
+        for &(w, role) in &wires {
+            write!(out, "    w{} -> w{}", w.get(), d.get()).unwrap();
+            match role {
+                Some(role) => writeln!(out, " [label={:?}];", role).unwrap(),
+                None => writeln!(out, ";").unwrap(),
+            }
+        }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+// What goes under the wire's name; a plain connection doesn't need
+// anything.
+fn op(gate: &Gate<Decl>) -> String {
+    match *gate {
+        Gate::Imm(value) => value.to_string(),
+        Gate::Not(_) => "NOT".to_owned(),
+        Gate::And(..) => "AND".to_owned(),
+        Gate::Or(x, y) if x == y => String::new(),
+        Gate::Or(..) => "OR".to_owned(),
+        Gate::LShift(_, sh) => format!("LSHIFT {}", sh.get()),
+        Gate::RShift(_, sh) => format!("RSHIFT {}", sh.get()),
+        Gate::Xor(..) => "XOR".to_owned(),
+        Gate::Add(..) => "ADD".to_owned(),
+        Gate::Sub(..) => "SUB".to_owned(),
+        Gate::Mux(..) => "MUX".to_owned(),
+        Gate::LShiftBy(..) => "LSHIFT".to_owned(),
+        Gate::RShiftBy(..) => "RSHIFT".to_owned(),
+    }
+}
+
+// What each input's for, where the order matters; `deps` order.
+fn roles(gate: &Gate<Decl>) -> Vec<Option<&'static str>> {
+    match *gate {
+        Gate::Sub(..) => vec![None, Some("minus")],
+        Gate::Mux(..) => vec![Some("if"), Some("then"), Some("else")],
+        Gate::LShiftBy(..) | Gate::RShiftBy(..) => vec![None, Some("by")],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parse::parse;
+    use util::Input;
+    use shells::graph;
+
+    fn lines(input: &str, outputs: &[&str], signals: bool) -> Vec<String> {
+        let insns = parse(Input::text(input)).unwrap();
+        graph(insns, outputs, signals).unwrap().lines().map(|l| l.to_owned()).collect()
+    }
+
+    #[test]
+    fn example() {
+        let g = lines("123 -> x\n456 -> y\nx AND y -> d\nx LSHIFT 2 -> f\nNOT y -> i\n\
+                       x -> a\n", &["d", "a"], false);
+        assert_eq!(g, vec!["digraph circuit {",
+                           "    rankdir=LR;",
+                           "    node [shape=box];",
+                           "    w0 [label=\"x\\n123\"];",
+                           "    w1 [label=\"y\\n456\"];",
+                           "    w2 [label=\"d\\nAND\", style=bold];",
+                           "    w0 -> w2;",
+                           "    w1 -> w2;",
+                           "    w3 [label=\"f\\nLSHIFT 2\"];",
+                           "    w0 -> w3;",
+                           "    w4 [label=\"i\\nNOT\"];",
+                           "    w1 -> w4;",
+                           "    w5 [label=\"a\", style=bold];",
+                           "    w0 -> w5;",
+                           "}"]);
+    }
+
+    #[test]
+    fn literals() {
+        let g = lines("1 AND x -> y\n3 -> x\nNOT 5 -> z\n2 OR 4 -> w\nz -> v\n",
+                      &["y", "w", "v"], true);
+        assert_eq!(&g[3..], &["    w1 [label=\"y\\nAND 1\\n= 1\", style=bold];",
+                              "    w2 -> w1;",
+                              "    w2 [label=\"x\\n3\\n= 3\"];",
+                              "    w4 [label=\"z\\nNOT 5\\n= 65530\"];",
+                              "    w7 [label=\"w\\nOR 2 4\\n= 6\", style=bold];",
+                              "    w8 [label=\"v\\n= 65530\", style=bold];",
+                              "    w4 -> w8;",
+                              "}"]);
+    }
+
+    #[test]
+    fn order() {
+        let g = lines("x SUB 1 -> y\n2 SUB x -> z\nMUX s x 7 -> m\n3 -> s\n9 -> x\n\
+                       x LSHIFT s -> l\n", &["y", "z", "m", "l"], true);
+        assert_eq!(&g[3..], &["    w1 [label=\"y\\nSUB minus 1\\n= 8\", style=bold];",
+                              "    w2 -> w1;",
+                              "    w2 [label=\"x\\n9\\n= 9\"];",
+                              "    w4 [label=\"z\\nSUB 2\\n= 65529\", style=bold];",
+                              "    w2 -> w4 [label=\"minus\"];",
+                              "    w6 [label=\"m\\nMUX else 7\\n= 9\", style=bold];",
+                              "    w7 -> w6 [label=\"if\"];",
+                              "    w2 -> w6 [label=\"then\"];",
+                              "    w7 [label=\"s\\n3\\n= 3\"];",
+                              "    w8 [label=\"l\\nLSHIFT\\n= 72\", style=bold];",
+                              "    w2 -> w8;",
+                              "    w7 -> w8 [label=\"by\"];",
+                              "}"]);
+    }
+
+    #[test]
+    fn cycles() {
+        let g = lines("NOT s -> s\ns AND 7 -> t\n", &["t"], true);
+        assert_eq!(&g[3..], &["    w0 [label=\"s\\nNOT\"];",
+                              "    w0 -> w0;",
+                              "    w2 [label=\"t\\nAND 7\", style=bold];",
+                              "    w0 -> w2;",
+                              "}"]);
+    }
+}
@@ -10,7 +10,7 @@ impl<'p, P: ProgramT + 'p> Strategy<'p, P> for Eager {
         EagerEval { prog: prog }
     }
 }
-struct EagerEval<'p, P: ProgramT + 'p> {
+pub struct EagerEval<'p, P: ProgramT + 'p> {
     prog: &'p P
 }
 impl<'p, P: ProgramT + 'p> Eval<'p, P, NoError> for EagerEval<'p, P> {
@@ -29,7 +29,7 @@ impl<'p, P: ProgramT + 'p> Strategy<'p, P> for CheckedEager {
     type Eval = CheckedEagerEval<'p, P>;
     fn load(&self, prog: &'p P) -> Self::Eval { CheckedEagerEval { prog: prog } }
 }
-struct CheckedEagerEval<'p, P: ProgramT + 'p> {
+pub struct CheckedEagerEval<'p, P: ProgramT + 'p> {
     prog: &'p P
 }
 impl<'p, P: ProgramT + 'p> Eval<'p, P, EagerError<P::OuterIdent>> for CheckedEagerEval<'p, P> {
@@ -32,6 +32,8 @@ pub trait Expr {
     type Value;
     fn eval<Error, F>(&self, env: F) -> Result<Self::Value, Error>
         where F: Fn(&Self::Ident) -> Result<Self::Value, Error>;
+    // Every identifier `eval` might look up, in the order it would.
+    fn deps<F>(&self, f: F) where F: FnMut(&Self::Ident);
 }
 
 pub trait ExprMap<AltId>: Expr {
@@ -59,6 +61,8 @@ pub type ProgResult<P, E> = Result<<<P as ProgramT>::Expr as Expr>::Value, E>;
 pub struct Decl(usize);
 impl Decl {
     pub fn get(self) -> usize { self.0 }
+    // All of them, for a program `len` long.
+    pub fn all(len: usize) -> impl Iterator<Item=Decl> { (0..len).map(Decl) }
 }
 
 #[derive(Clone)]
@@ -155,6 +159,17 @@ pub struct Program<Ex, OuterIdent> {
     text: Box<[Ex]>,
     debug_info: Box<[OuterIdent]>,
 }
+impl<Ex, OuterIdent> Program<Ex, OuterIdent> {
+    // For passes that turn one program into another.
+    pub fn from_parts(entries: Vec<Decl>, text: Vec<Ex>, debug_info: Vec<OuterIdent>) -> Self {
+        assert_eq!(text.len(), debug_info.len());
+        Program {
+            entries: entries.into_boxed_slice(),
+            text: text.into_boxed_slice(),
+            debug_info: debug_info.into_boxed_slice()
+        }
+    }
+}
 impl<Ex, OuterIdent> ProgramT for Program<Ex, OuterIdent>
         where Ex: Expr<Ident=Decl>, OuterIdent: Clone + Debug {
     type Expr = Ex;
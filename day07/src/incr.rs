@@ -0,0 +1,241 @@
+use generic::{Expr,Decl,ProgramT,LinkerError,ProgResult};
+use topo::{self,TopoError};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type Value<P> = <<P as ProgramT>::Expr as Expr>::Value;
+
+// Holds on to a program and whatever's been worked out so far, so that
+// wires can be overridden (or not) and only what depends on them has to
+// be done again.  Evaluation doesn't recurse, like `Topo`.
+//
+// (It'd be `override`, but that's a reserved word.)
+pub struct Incremental<P: ProgramT> {
+    prog: P,
+    decls: Vec<Decl>,
+    names: HashMap<P::OuterIdent, Decl>,
+    deps: Vec<Vec<usize>>,
+    users: Vec<Vec<usize>>,
+    overrides: Vec<Option<Value<P>>>,
+    values: Vec<Option<Value<P>>>,
+    evals: usize,
+}
+
+impl<P: ProgramT> Incremental<P>
+    where P::OuterIdent: Eq + Hash,
+          Value<P>: Clone + PartialEq {
+    pub fn new(prog: P) -> Self {
+        let decls: Vec<Decl> = Decl::all(prog.len()).collect();
+        let names = decls.iter().map(|&d| (prog.debug(d), d)).collect();
+        let deps = topo::deps(&prog);
+        let mut users = vec![Vec::new(); decls.len()];
+        for (i, ds) in deps.iter().enumerate() {
+            for &j in ds {
+                users[j].push(i);
+            }
+        }
+        Incremental {
+            overrides: vec![None; decls.len()],
+            values: vec![None; decls.len()],
+            prog, decls, names, deps, users,
+            evals: 0,
+        }
+    }
+
+    pub fn decl<Q: ?Sized>(&self, wire: &Q) -> Result<Decl, LinkerError<P::OuterIdent>>
+        where Q: Eq + Hash + ToOwned<Owned=P::OuterIdent>,
+              P::OuterIdent: Borrow<Q> {
+        self.names.get(wire).cloned()
+            .ok_or_else(|| LinkerError::UndefinedSymbol(wire.to_owned()))
+    }
+    // How many gates have been evaluated, all told.
+    pub fn evals(&self) -> usize { self.evals }
+
+    // Makes `wire` carry `value`, whatever its gate says.
+    pub fn set_override<Q: ?Sized>(&mut self, wire: &Q, value: Value<P>)
+                                   -> Result<(), LinkerError<P::OuterIdent>>
+        where Q: Eq + Hash + ToOwned<Owned=P::OuterIdent>,
+              P::OuterIdent: Borrow<Q> {
+        let i = self.decl(wire)?.get();
+        if self.values[i].as_ref() != Some(&value) {
+            self.invalidate(i);
+        }
+        self.values[i] = Some(value.clone());
+        self.overrides[i] = Some(value);
+        Ok(())
+    }
+    pub fn clear_override<Q: ?Sized>(&mut self, wire: &Q)
+                                     -> Result<(), LinkerError<P::OuterIdent>>
+        where Q: Eq + Hash + ToOwned<Owned=P::OuterIdent>,
+              P::OuterIdent: Borrow<Q> {
+        let i = self.decl(wire)?.get();
+        if self.overrides[i].take().is_some() {
+            self.invalidate(i);
+        }
+        Ok(())
+    }
+
+    // Forgets the value of `i` and everything using it.  Anything that
+    // already has no value can't have anything using it with one (other
+    // than overrides, which don't count), so that's as far as it goes.
+    fn invalidate(&mut self, i: usize) {
+        let mut todo = vec![i];
+        while let Some(i) = todo.pop() {
+            if self.values[i].take().is_some() {
+                todo.extend(self.users[i].iter().cloned());
+            }
+        }
+    }
+
+    pub fn run(&mut self, entry: Decl) -> ProgResult<P, TopoError<P::OuterIdent>> {
+        // Which declarations are waiting on their inputs; they're always
+        // the ones on the way down from `entry` to the top of the stack.
+        let mut waiting = vec![false; self.decls.len()];
+        let mut stack = vec![entry.get()];
+        while let Some(&i) = stack.last() {
+            if self.values[i].is_some() {
+                stack.pop();
+                continue;
+            }
+            if let Some(ref value) = self.overrides[i] {
+                self.values[i] = Some(value.clone());
+                stack.pop();
+                continue;
+            }
+            if !waiting[i] {
+                waiting[i] = true;
+                for &j in &self.deps[i] {
+                    if self.values[j].is_none() {
+                        if waiting[j] {
+                            return Err(TopoError::Cycle(self.cycles_under(entry)));
+                        }
+                        stack.push(j);
+                    }
+                }
+                continue;
+            }
+            let value = {
+                let values = &self.values;
+                self.prog.lookup(self.decls[i]).eval(|d| values[d.get()].clone().ok_or(()))
+            };
+            self.values[i] = Some(value.expect("inputs should be done by now"));
+            self.evals += 1;
+            stack.pop();
+        }
+        Ok(self.values[entry.get()].clone().unwrap())
+    }
+    // The program's entry points.
+    pub fn outputs(&mut self) -> Result<Vec<Value<P>>, TopoError<P::OuterIdent>> {
+        let entries = self.prog.entries().to_vec();
+        entries.into_iter().map(|d| self.run(d)).collect()
+    }
+
+    // Overridden wires don't use anything, which might have broken some
+    // cycles.
+    fn cycles_under(&self, entry: Decl) -> Vec<P::OuterIdent> {
+        let deps: Vec<Vec<usize>> = self.deps.iter().zip(&self.overrides).map(|(ds, ov)| {
+            if ov.is_some() { vec![] } else { ds.clone() }
+        }).collect();
+        let mut cyclic = vec![false; deps.len()];
+        for comp in topo::components(&deps) {
+            if topo::is_cycle(&deps, &comp) {
+                for &j in &comp {
+                    cyclic[j] = true;
+                }
+            }
+        }
+        topo::cycles_under(&deps, &cyclic, entry).into_iter()
+            .map(|d| self.prog.debug(d))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ast::Gate;
+    use shells::circuit;
+
+    fn s(s: &str) -> String { s.to_owned() }
+
+    fn example() -> Vec<(Gate<String>, String)> {
+        vec![(Gate::Imm(123), s("x")),
+             (Gate::Imm(456), s("y")),
+             (Gate::And(s("x"), s("y")), s("d")),
+             (Gate::Or(s("x"), s("y")), s("e")),
+             (Gate::lshift(s("x"), 2), s("f")),
+             (Gate::rshift(s("y"), 2), s("g")),
+             (Gate::Not(s("x")), s("h")),
+             (Gate::Not(s("y")), s("i"))]
+    }
+
+    #[test]
+    fn overrides() {
+        let mut c = circuit(example(), &["d", "e", "f", "g", "h", "i"]).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![72, 507, 492, 114, 65412, 65079]);
+        assert_eq!(c.evals(), 8);
+        assert_eq!(c.outputs().unwrap(), vec![72, 507, 492, 114, 65412, 65079]);
+        assert_eq!(c.evals(), 8);
+        // Only d, e, g and i use y.
+        c.set_override("y", 0xff).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![123, 255, 492, 63, 65412, 65280]);
+        assert_eq!(c.evals(), 12);
+        // Same again changes nothing.
+        c.set_override("y", 0xff).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![123, 255, 492, 63, 65412, 65280]);
+        assert_eq!(c.evals(), 12);
+        // Overriding something else that's used: d, e, f and h (and not y).
+        c.set_override("x", 0).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![0, 255, 0, 63, 65535, 65280]);
+        assert_eq!(c.evals(), 16);
+        c.clear_override("y").unwrap();
+        c.clear_override("y").unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![0, 456, 0, 114, 65535, 65079]);
+        assert_eq!(c.evals(), 21);
+        c.clear_override("x").unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![72, 507, 492, 114, 65412, 65079]);
+        assert_eq!(c.evals(), 26);
+    }
+
+    #[test]
+    fn only_whats_asked() {
+        let mut c = circuit(example(), &["f"]).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![492]);
+        assert_eq!(c.evals(), 2);
+        c.set_override("y", 1).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![492]);
+        assert_eq!(c.evals(), 2);
+        let h = c.decl("h").unwrap();
+        assert_eq!(c.run(h).unwrap(), 65412);
+        assert_eq!(c.evals(), 3);
+    }
+
+    #[test]
+    fn cycles() {
+        let mut c = circuit(vec![(Gate::Not(s("b")), s("a")),
+                                 (Gate::Not(s("a")), s("b")),
+                                 (Gate::Not(s("b")), s("c"))], &["c"]).unwrap();
+        assert_eq!(format!("{:?}", c.outputs().unwrap_err()), "Cycle([\"a\", \"b\"])");
+        c.set_override("a", 0xff00).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![0xff00]);
+        c.clear_override("a").unwrap();
+        assert_eq!(format!("{:?}", c.outputs().unwrap_err()), "Cycle([\"a\", \"b\"])");
+        assert_eq!(format!("{:?}", c.set_override("z", 1).unwrap_err()),
+                   "UndefinedSymbol(\"z\")");
+    }
+
+    #[test]
+    fn deep() {
+        let n = 200000;
+        let mut insns: Vec<_> = (1..n)
+            .map(|i| (Gate::Not(format!("w{}", i - 1)), format!("w{}", i)))
+            .collect();
+        insns.push((Gate::Imm(1), s("w0")));
+        let last = format!("w{}", n - 1);
+        let mut c = circuit(insns, &[&last]).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![0xfffe]);
+        c.set_override("w1", 0).unwrap();
+        assert_eq!(c.outputs().unwrap(), vec![0]);
+        assert_eq!(c.evals(), 2 * n - 2);
+    }
+}
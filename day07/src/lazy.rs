@@ -26,7 +26,7 @@ impl<'p, P: ProgramT + 'p, M> Strategy<'p, P> for GenLazy<M>
         }
     }
 }
-struct GenLazyEval<'p, P: ProgramT + 'p, M>
+pub struct GenLazyEval<'p, P: ProgramT + 'p, M>
     where M: Memo<<P::Expr as Expr>::Value> {
     prog: &'p P,
     memos: Box<[M]>,
@@ -68,10 +68,10 @@ impl<T> Memo<T> for NullMemo<T> {
     }
 }
 
-struct UnsafeM;
+pub struct UnsafeM;
 impl<T: Clone> MemoFlavor<T> for UnsafeM { type Memo = UnsafeMemo<T>; }
 
-struct UnsafeMemo<T>(RefCell<Option<T>>);
+pub struct UnsafeMemo<T>(RefCell<Option<T>>);
 impl<T: Clone> Memo<T> for UnsafeMemo<T> {
     fn new() -> Self { UnsafeMemo(RefCell::new(None)) }
     fn apply<E, F, X>(&self, f: F, _x: X) -> Result<T, E>
@@ -87,10 +87,10 @@ impl<T: Clone> Memo<T> for UnsafeMemo<T> {
     }
 }
 
-struct SafeM;
+pub struct SafeM;
 impl<T: Clone> MemoFlavor<T> for SafeM { type Memo = SafeMemo<T>; }
 
-struct SafeMemo<T>(RefCell<SafeMemoInner<T>>);
+pub struct SafeMemo<T>(RefCell<SafeMemoInner<T>>);
 impl<T: Clone> Memo<T> for SafeMemo<T> {
     fn new() -> Self { SafeMemo(RefCell::new(SafeMemoInner::New)) }
     fn apply<E, F, X>(&self, f: F, x: X) -> Result<T, E>
@@ -0,0 +1,111 @@
+extern crate util;
+
+mod generic;
+mod eager;
+mod ast;
+mod lazy;
+mod topo;
+mod incr;
+mod opt;
+mod dot;
+mod shells;
+mod parse;
+
+use std::env;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{stdout, Write};
+use ast::Signal;
+use parse::parse;
+use shells::Insn;
+use util::Input;
+use util::solution::{json_mode, Answer, Solution};
+
+pub fn main() {
+    json_mode(7, &Day);
+    let mut dot_file = None;
+    let mut with_signals = false;
+    let mut topo = false;
+    let mut overrides = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--dot" => dot_file = Some(args.next().expect("--dot needs a file name, or -")),
+            "--signals" => with_signals = true,
+            // Evaluate without recursing, and say which wires are in a
+            // cycle if there is one.
+            "--topo" => topo = true,
+            // Cut the wire and feed it this instead, like part 2 does.
+            "--set" => overrides.push(args.next().expect("--set needs wire=value")),
+            huh => panic!("unknown argument {:?}", huh),
+        }
+    }
+    let stuff = parse(Input::stdin()).unwrap_or_else(|err| panic!("{}", err));
+    // Just the graph, instead of the answer; `-` is stdout.
+    if let Some(path) = dot_file {
+        let graph = shells::graph(stuff.clone(), &["a"], with_signals)
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        let mut out: Box<dyn Write> = if path == "-" {
+            Box::new(stdout())
+        } else {
+            Box::new(File::create(&path).unwrap_or_else(|err| panic!("{}: {}", path, err)))
+        };
+        out.write_all(graph.as_bytes()).unwrap();
+        return;
+    }
+    if !overrides.is_empty() {
+        let mut circuit = shells::circuit(stuff, &["a"]).unwrap_or_else(|err| panic!("{:?}", err));
+        for set in &overrides {
+            let mut kv = set.splitn(2, '=');
+            let wire = kv.next().unwrap();
+            let value = kv.next().and_then(|v| v.parse().ok())
+                .unwrap_or_else(|| panic!("--set needs wire=value, not {:?}", set));
+            circuit.set_override(wire, value).unwrap_or_else(|err| panic!("{:?}", err));
+        }
+        let thing = circuit.outputs().unwrap_or_else(|err| panic!("a is {}", err))[0];
+        println!("{} -> a", thing);
+        eprintln!("({} gates evaluated)", circuit.evals());
+        return;
+    }
+    // println!("stuff = {:?}", stuff);
+    let thing = if topo {
+        match shells::eval_topo(stuff, &["a"]) {
+            Ok(sigs) => sigs[0],
+            Err(shells::Error::EvalError(err)) => panic!("a is {}", err),
+            Err(err) => panic!("{:?}", err),
+        }
+    } else {
+        shells::eval_lazy(stuff, &["a"]).unwrap()[0]
+    };
+    println!("{} -> a", thing);
+}
+
+fn debug<E: Debug>(e: E) -> String {
+    format!("{:?}", e)
+}
+
+fn wire_a(insns: Vec<Insn>) -> Result<Signal, String> {
+    shells::eval_optimized(insns, &["a"]).map(|(sigs, _)| sigs[0]).map_err(debug)
+}
+
+fn rewired_a(insns: Vec<Insn>) -> Result<Signal, String> {
+    let mut circuit = shells::circuit(insns, &["a"]).map_err(debug)?;
+    let a = circuit.outputs().map_err(debug)?[0];
+    // Cut whatever drives wire b and feed it that signal instead.
+    circuit.set_override("b", a).map_err(debug)?;
+    let rewired = circuit.outputs().map_err(debug)?[0];
+    // And putting b back should put a back.
+    circuit.clear_override("b").map_err(debug)?;
+    debug_assert_eq!(circuit.outputs().ok(), Some(vec![a]));
+    Ok(rewired)
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        Ok(wire_a(parse(Input::text(input))?)?.to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        Ok(rewired_a(parse(Input::text(input))?)?.to_string())
+    }
+}
@@ -0,0 +1,386 @@
+use ast::{Gate,Signal,Shift};
+use generic::{Decl,Expr,ExprMap,Program,ProgramT};
+use topo;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Debug;
+
+// What `optimize` did.  Every gate it gets rid of was folded into a
+// constant and then merged or not needed, or made redundant by an
+// identity, or a duplicate, or just not used.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Report {
+    pub before: usize,
+    pub after: usize,
+    pub folded: usize,
+    pub identities: usize,
+    pub merged: usize,
+}
+impl Report {
+    pub fn removed(&self) -> usize { self.before - self.after }
+}
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} gates -> {} ({} removed; {} folded, {} identities, {} merged)",
+               self.before, self.after, self.removed(), self.folded, self.identities, self.merged)
+    }
+}
+
+// What a gate turns out to be, given what's known about its inputs.
+enum Simple {
+    Const(Signal),
+    Wire(usize),
+    Gate(Gate<usize>),
+}
+
+// If exactly one of `x` and `y` is a constant: the other one, and the
+// constant.
+fn with_const(consts: &[Option<Signal>], x: usize, y: usize) -> Option<(usize, Signal)> {
+    match (consts[x], consts[y]) {
+        (None, Some(c)) => Some((x, c)),
+        (Some(c), None) => Some((y, c)),
+        _ => None,
+    }
+}
+
+// The cycles that `g` depends on, from `cycles` for each of its inputs.
+fn under(g: &Gate<usize>, cycles: &[Vec<usize>]) -> Vec<usize> {
+    let mut under = Vec::new();
+    g.deps(|&j| under.extend(cycles[j].iter().cloned()));
+    under.sort();
+    under.dedup();
+    under
+}
+
+// Like `rules`, except that a wire stuck behind a cycle stays stuck, the
+// way it would be without optimizing: `x AND 0` has no value if `x`
+// doesn't.  Overrides can break each cycle separately, so it has to
+// stay behind the same ones.
+fn simplify(g: Gate<usize>, consts: &[Option<Signal>], done: &[Option<Gate<usize>>],
+            cycles: &[Vec<usize>]) -> Simple {
+    let before = under(&g, cycles);
+    if before.is_empty() {
+        return rules(g, consts, done);
+    }
+    match rules(g.clone(), consts, done) {
+        Simple::Wire(j) if cycles[j] == before => Simple::Wire(j),
+        Simple::Gate(h) if under(&h, cycles) == before => Simple::Gate(h),
+        _ => Simple::Gate(g),
+    }
+}
+
+fn rules(g: Gate<usize>, consts: &[Option<Signal>], done: &[Option<Gate<usize>>]) -> Simple {
+    if let Ok(value) = g.eval(|&j| consts[j].ok_or(())) {
+        return Simple::Const(value);
+    }
+    match g {
+        Gate::And(x, y) | Gate::Or(x, y) if x == y => Simple::Wire(x),
+        Gate::And(x, y) => match with_const(consts, x, y) {
+            Some((_, 0)) => Simple::Const(0),
+            Some((w, 0xffff)) => Simple::Wire(w),
+            _ => Simple::Gate(Gate::And(x.min(y), x.max(y))),
+        },
+        Gate::Or(x, y) => match with_const(consts, x, y) {
+            Some((w, 0)) => Simple::Wire(w),
+            Some((_, 0xffff)) => Simple::Const(0xffff),
+            _ => Simple::Gate(Gate::Or(x.min(y), x.max(y))),
+        },
+        Gate::LShift(x, sh) | Gate::RShift(x, sh) if sh == Shift::new(0).unwrap() =>
+            Simple::Wire(x),
+        Gate::Xor(x, y) | Gate::Sub(x, y) if x == y => Simple::Const(0),
+        Gate::Xor(x, y) => match with_const(consts, x, y) {
+            Some((w, 0)) => Simple::Wire(w),
+            _ => Simple::Gate(Gate::Xor(x.min(y), x.max(y))),
+        },
+        Gate::Add(x, y) => match with_const(consts, x, y) {
+            Some((w, 0)) => Simple::Wire(w),
+            _ => Simple::Gate(Gate::Add(x.min(y), x.max(y))),
+        },
+        Gate::Sub(x, y) if consts[y] == Some(0) => Simple::Wire(x),
+        Gate::Mux(sel, x, y) => match consts[sel] {
+            Some(0) => Simple::Wire(y),
+            Some(_) => Simple::Wire(x),
+            None if x == y => Simple::Wire(x),
+            None => Simple::Gate(g),
+        },
+        Gate::LShiftBy(x, by) | Gate::RShiftBy(x, by) => match consts[by] {
+            Some(0) => Simple::Wire(x),
+            Some(by) if by < 16 => {
+                let sh = Shift::new(by as u8).unwrap();
+                Simple::Gate(match g {
+                    Gate::LShiftBy(..) => Gate::LShift(x, sh),
+                    _ => Gate::RShift(x, sh),
+                })
+            }
+            Some(_) => Simple::Const(0),
+            None => Simple::Gate(g),
+        },
+        Gate::Not(y) => match done[y] {
+            Some(Gate::Not(x)) => Simple::Wire(x),
+            _ => Simple::Gate(g),
+        },
+        g => Simple::Gate(g),
+    }
+}
+
+// Folds constants, forwards identities like `x AND x` and `NOT NOT x`,
+// merges gates that do the same thing to the same inputs, and drops
+// whatever the entries don't need.  Wires that were merged away take
+// their names with them; anything in a cycle is left as it is, and
+// anything depending on one still does.
+pub fn optimize<Id>(prog: &Program<Gate<Decl>, Id>) -> (Program<Gate<Decl>, Id>, Report)
+    where Id: Clone + Debug {
+    let n = prog.len();
+    let decls: Vec<Decl> = Decl::all(n).collect();
+    let deps = topo::deps(prog);
+    let mut report = Report { before: n, ..Report::default() };
+    // What each wire has turned into, and what stands for it now.
+    let mut done: Vec<Option<Gate<usize>>> = vec![None; n];
+    let mut repr: Vec<usize> = (0..n).collect();
+    let mut consts = vec![None; n];
+    // Which cycles each wire depends on, by their first declarations.
+    let mut cycles: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut seen = HashMap::new();
+    let mut cyclic = Vec::new();
+    for comp in topo::components(&deps) {
+        if topo::is_cycle(&deps, &comp) {
+            let mut under = vec![comp[0]];
+            for &i in &comp {
+                under.extend(deps[i].iter().flat_map(|&j| cycles[j].iter().cloned()));
+            }
+            under.sort();
+            under.dedup();
+            for &i in &comp {
+                cycles[i] = under.clone();
+            }
+            cyclic.extend(comp);
+            continue;
+        }
+        let i = comp[0];
+        let orig = prog.lookup(decls[i]);
+        let g = orig.idmap(|d| repr[d.get()]);
+        cycles[i] = under(&g, &cycles);
+        let g = match simplify(g, &consts, &done, &cycles) {
+            Simple::Const(value) => {
+                if *orig != Gate::Imm(value) {
+                    report.folded += 1;
+                }
+                Gate::Imm(value)
+            }
+            Simple::Wire(j) => {
+                report.identities += 1;
+                repr[i] = j;
+                continue;
+            }
+            Simple::Gate(g) => g,
+        };
+        if let Some(&j) = seen.get(&g) {
+            report.merged += 1;
+            repr[i] = j;
+            continue;
+        }
+        if let Gate::Imm(value) = g {
+            consts[i] = Some(value);
+        }
+        seen.insert(g.clone(), i);
+        done[i] = Some(g);
+    }
+    // Cycles can use things that come after them, so they're done last.
+    for i in cyclic {
+        done[i] = Some(prog.lookup(decls[i]).idmap(|d| repr[d.get()]));
+    }
+
+    let entries: Vec<usize> = prog.entries().iter().map(|d| repr[d.get()]).collect();
+    let mut live = vec![false; n];
+    let mut todo = entries.clone();
+    while let Some(i) = todo.pop() {
+        if !live[i] {
+            live[i] = true;
+            done[i].as_ref().unwrap().deps(|&j| todo.push(j));
+        }
+    }
+    let kept: Vec<usize> = (0..n).filter(|&i| live[i]).collect();
+    let new_decls: Vec<Decl> = Decl::all(kept.len()).collect();
+    let mut renumber = vec![None; n];
+    for (k, &i) in kept.iter().enumerate() {
+        renumber[i] = Some(new_decls[k]);
+    }
+    let to_new = |&j: &usize| renumber[j].unwrap();
+    let text = kept.iter().map(|&i| done[i].as_ref().unwrap().idmap(to_new)).collect();
+    let names = kept.iter().map(|&i| prog.debug(decls[i])).collect();
+    report.after = kept.len();
+    (Program::from_parts(entries.iter().map(to_new).collect(), text, names), report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Report;
+    use ast::Gate;
+    use generic::ProgramT;
+    use incr::Incremental;
+    use parse::parse;
+    use shells::{circuit, eval_optimized, eval_topo, optimized};
+    use util::Input;
+
+    fn s(s: &str) -> String { s.to_owned() }
+
+    #[test]
+    fn folding() {
+        let insns = parse(Input::text(
+            "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\n\
+             y RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i\n")).unwrap();
+        let outs = ["d", "e", "f", "g", "h", "i", "x", "y"];
+        let (prog, report) = optimized(insns, &outs).unwrap();
+        assert_eq!(report, Report { before: 8, after: 8, folded: 6, identities: 0, merged: 0 });
+        for &d in prog.entries() {
+            match *prog.lookup(d) {
+                Gate::Imm(_) => (),
+                ref g => panic!("{} is still {:?}", prog.debug(d), g),
+            }
+        }
+        let (prog, report) = optimized(parse(Input::text("123 -> x\nNOT x -> h\n")).unwrap(),
+                                       &["h"]).unwrap();
+        assert_eq!(report.removed(), 1);
+        assert_eq!(prog.lookup(prog.entries()[0]), &Gate::Imm(65412));
+        assert_eq!(prog.debug(prog.entries()[0]), "h");
+    }
+
+    #[test]
+    fn constants_merge() {
+        let insns = parse(Input::text(
+            "p -> q\nq AND q -> r\n0 OR r -> t\nt LSHIFT 0 -> u\nNOT u -> v\n\
+             NOT v -> w\n65535 AND w -> z\n5 -> p\n1 AND k -> o\nz -> k\n")).unwrap();
+        let (out, report) = eval_optimized(insns, &["z", "o"]).unwrap();
+        assert_eq!(out, vec![5, 1]);
+        // Everything's 5 except v, o and the literals; all the 5s and 1s
+        // are the same, and only one of each is needed.
+        assert_eq!(report, Report { before: 13, after: 2, folded: 9, identities: 0, merged: 8 });
+    }
+
+    // `s` is in a cycle, so it stands in for a wire whose value isn't
+    // known until it's overridden.
+    #[test]
+    fn identities() {
+        let insns = parse(Input::text(
+            "NOT s -> s\ns AND s -> p\np -> q\nq AND q -> r\n0 OR r -> t\n\
+             t LSHIFT 0 -> u\nNOT u -> v\nNOT v -> w\n65535 AND w -> z\nz -> k\n")).unwrap();
+        let (prog, report) = optimized(insns, &["z", "k"]).unwrap();
+        assert_eq!(report, Report { before: 12, after: 1, folded: 0, identities: 8, merged: 0 });
+        let s = prog.entries()[0];
+        assert_eq!(prog.entries(), &[s, s]);
+        assert_eq!(prog.debug(s), "s");
+        assert_eq!(prog.lookup(s), &Gate::Not(s));
+    }
+
+    #[test]
+    fn more_identities() {
+        let insns = parse(Input::text(
+            "NOT s -> s\n3 -> k\nk XOR k -> z\ns SUB z -> a\nMUX z s t -> b\n\
+             NOT a -> t\n20 -> n\nb LSHIFT n -> c\nMUX a c c -> d\n\
+             d RSHIFT 1 -> e\ne ADD z -> f\ns XOR s -> x\nx AND z -> y\n")).unwrap();
+        let (prog, report) = optimized(insns, &["f", "a", "y"]).unwrap();
+        // z is 0, so a is s, b is t, and f is e; d is c either way.  But
+        // c isn't 0, even shifted that far, and neither are x and y:
+        // they're stuck behind s.
+        assert_eq!(report, Report { before: 13, after: 8, folded: 1, identities: 4, merged: 0 });
+        let names: Vec<_> = prog.entries().iter().map(|&d| prog.debug(d)).collect();
+        assert_eq!(names, vec!["e", "s", "y"]);
+    }
+
+    // Constants that absorb the other input don't help if that input has
+    // no value; the answer is the same as without optimizing.
+    #[test]
+    fn absorbing() {
+        for text in &["NOT s -> s\n0 AND s -> a\n", "NOT s -> s\ns OR 65535 -> a\n"] {
+            let insns = parse(Input::text(text)).unwrap();
+            let (prog, _) = optimized(insns.clone(), &["a"]).unwrap();
+            assert_eq!(prog.len(), 3, "{}", text);
+            assert_eq!(format!("{:?}", eval_optimized(insns.clone(), &["a"]).unwrap_err()),
+                       format!("{:?}", eval_topo(insns, &["a"]).unwrap_err()));
+        }
+    }
+
+    // Likewise for inputs that cancel out, or aren't selected, or are
+    // shifted all the way out.
+    #[test]
+    fn dropped_inputs() {
+        for text in &["NOT s -> s\ns XOR s -> a\n", "NOT s -> s\ns SUB s -> a\n",
+                      "NOT s -> s\n5 -> x\nMUX 1 x s -> a\n", "NOT s -> s\nMUX 0 s 5 -> a\n",
+                      "NOT s -> s\nMUX s 5 5 -> a\n", "NOT s -> s\n20 -> n\ns LSHIFT n -> a\n",
+                      "NOT s -> s\n16 -> n\ns RSHIFT n -> a\n"] {
+            let insns = parse(Input::text(text)).unwrap();
+            assert_eq!(format!("{:?}", eval_optimized(insns.clone(), &["a"]).unwrap_err()),
+                       format!("{:?}", eval_topo(insns, &["a"]).unwrap_err()), "{}", text);
+        }
+    }
+
+    #[test]
+    fn merging() {
+        let insns = vec![(Gate::Not(s("s")), s("s")),
+                         (Gate::And(s("s"), s("a")), s("x")),
+                         (Gate::And(s("a"), s("s")), s("y")),
+                         (Gate::Or(s("x"), s("y")), s("z")),
+                         (Gate::Not(s("z")), s("c")),
+                         (Gate::Not(s("x")), s("d")),
+                         (Gate::Imm(7), s("a")),
+                         (Gate::Imm(7), s("e"))];
+        let (prog, report) = optimized(insns, &["c", "d", "z", "e"]).unwrap();
+        // y is x, so z is x OR x, so c and d are the same.
+        assert_eq!(report, Report { before: 8, after: 4, folded: 0, identities: 1, merged: 3 });
+        let names: Vec<_> = prog.entries().iter().map(|&d| prog.debug(d)).collect();
+        assert_eq!(names, vec!["c", "c", "x", "a"]);
+    }
+
+    // Pseudo-random circuits with three cycles as inputs, so there's some
+    // of everything going on; optimizing shouldn't change the answers.
+    // The first two are overridden; the last one isn't, so whatever uses
+    // it should have no value either way.
+    #[test]
+    fn same_answers() {
+        let mut seed = 12345u32;
+        let mut rand = |n: usize| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as usize % n
+        };
+        for _ in 0..100 {
+            let n = 1 + rand(40);
+            let mut names = vec![s("in0"), s("in1"), s("in2")];
+            let mut insns = vec![(Gate::Not(s("in0")), s("in0")), (Gate::Not(s("in1")), s("in1")),
+                                 (Gate::Not(s("in2")), s("in2"))];
+            for k in 0..n {
+                let arg = |r: usize| names[r % names.len()].clone();
+                let gate = match rand(14) {
+                    0 => Gate::Imm([0, 1, 3, 16, 0xffff, 42][rand(6)]),
+                    1 => Gate::Not(arg(rand(1000))),
+                    2 => Gate::And(arg(rand(1000)), arg(rand(1000))),
+                    3 => Gate::Or(arg(rand(1000)), arg(rand(1000))),
+                    4 => Gate::lshift(arg(rand(1000)), rand(3) as u8),
+                    5 => Gate::rshift(arg(rand(1000)), rand(3) as u8),
+                    6 => { let a = arg(rand(1000)); Gate::Or(a.clone(), a) }
+                    7 => Gate::Xor(arg(rand(1000)), arg(rand(1000))),
+                    8 => Gate::Add(arg(rand(1000)), arg(rand(1000))),
+                    9 => Gate::Sub(arg(rand(1000)), arg(rand(1000))),
+                    10 => Gate::Mux(arg(rand(1000)), arg(rand(1000)), arg(rand(1000))),
+                    11 => Gate::LShiftBy(arg(rand(1000)), arg(rand(1000))),
+                    12 => Gate::RShiftBy(arg(rand(1000)), arg(rand(1000))),
+                    _ => Gate::And(s("in0"), arg(rand(1000))),
+                };
+                insns.push((gate, format!("w{}", k)));
+                names.push(format!("w{}", k));
+            }
+            let outs: Vec<String> = (0..3).map(|_| format!("w{}", rand(n))).collect();
+            let outs: Vec<&str> = outs.iter().map(|s| &s[..]).collect();
+            let mut before = circuit(insns.clone(), &outs).unwrap();
+            let (prog, _) = optimized(insns.clone(), &outs).unwrap();
+            let mut after = Incremental::new(prog);
+            for &(in0, in1) in &[(0, 0), (0xffff, 1), (0x1234, 0xfedc)] {
+                before.set_override("in0", in0).unwrap();
+                before.set_override("in1", in1).unwrap();
+                // They might not be there any more.
+                let _ = after.set_override("in0", in0);
+                let _ = after.set_override("in1", in1);
+                assert_eq!(after.outputs().ok(), before.outputs().ok(), "{:?}", insns);
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use ast::{Signal,Gate,Shift};
 use generic::ExprMap;
 use shells::Insn;
-use std::io::BufRead;
 use std::str::FromStr;
+use util::Input;
+use util::input::LineError;
+use util::scan::Scanner;
 
 fn wrangle_id(s: &str) -> Result<String, Signal> {
     match Signal::from_str(&s) {
@@ -11,43 +13,66 @@ fn wrangle_id(s: &str) -> Result<String, Signal> {
     }
 }
 
-fn mkshift(lnum: usize, s: &str) -> Shift {
-    Shift::new(u8::from_str(s).unwrap())
-        .unwrap_or_else(|| panic!("line {}: shift count out of range", lnum))
+type Operand = Result<String, Signal>;
+
+fn operand(s: &mut Scanner) -> Result<Operand, LineError> {
+    Ok(wrangle_id(s.word()?))
 }
 
-pub fn parse<B: BufRead>(input: B) -> Vec<Insn> {
-    let mut insns = Vec::new();
-    let mut fixups: usize = 0;
-    for (lnum, line) in input.lines().enumerate() {
-        let line = line.expect("I/O error");
-        let mut words: Vec<_> = line
-            .split(char::is_whitespace)
-            .filter(|s| s.len() > 0)
-            .collect();
-        assert!(words.len() >= 3, "line {}: too few tokens", lnum);
-        assert!(words.len() <= 5, "line {}: too many tokens", lnum);
-        let dst = words.pop().unwrap().to_owned();
-        let arrow = words.pop().unwrap();
-        assert!(arrow == "->", "line {}: token {:?} is not an arrow", lnum, arrow);
-        let expr: Gate<Result<String, Signal>> = match words.len() {
-            1 => match wrangle_id(words[0]) {
+fn shift(s: &mut Scanner) -> Result<Shift, LineError> {
+    Ok(Shift::new(s.int_in(0..=15)?).expect("shift count should be in range"))
+}
+
+// Shifting by a number means a `Shift`, which has to be in range;
+// anything else is a wire.
+fn by_wire(s: &Scanner) -> bool {
+    !s.rest().starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn gate(s: &mut Scanner) -> Result<(Gate<Operand>, String), LineError> {
+    let expr = if s.opt("NOT") {
+        Gate::Not(operand(s)?)
+    } else if s.opt("MUX") {
+        Gate::Mux(operand(s)?, operand(s)?, operand(s)?)
+    } else {
+        let lhs = operand(s)?;
+        if s.opt("AND") {
+            Gate::And(lhs, operand(s)?)
+        } else if s.opt("OR") {
+            Gate::Or(lhs, operand(s)?)
+        } else if s.opt("XOR") {
+            Gate::Xor(lhs, operand(s)?)
+        } else if s.opt("ADD") {
+            Gate::Add(lhs, operand(s)?)
+        } else if s.opt("SUB") {
+            Gate::Sub(lhs, operand(s)?)
+        } else if s.opt("LSHIFT") {
+            if by_wire(s) {
+                Gate::LShiftBy(lhs, operand(s)?)
+            } else {
+                Gate::LShift(lhs, shift(s)?)
+            }
+        } else if s.opt("RSHIFT") {
+            if by_wire(s) {
+                Gate::RShiftBy(lhs, operand(s)?)
+            } else {
+                Gate::RShift(lhs, shift(s)?)
+            }
+        } else {
+            match lhs {
                 Err(imm) => Gate::Imm(imm),
                 Ok(id) => Gate::Or(Ok(id.clone()), Ok(id)),
-            },
-            2 => match words[0] {
-                "NOT" => Gate::Not(wrangle_id(words[1])),
-                huh => panic!("line {}: bad unary operator {:?}", lnum, huh)
-            },
-            3 => match words[1] {
-                "AND" => Gate::And(wrangle_id(words[0]), wrangle_id(words[2])),
-                "OR" => Gate::Or(wrangle_id(words[0]), wrangle_id(words[2])),
-                "LSHIFT" => Gate::LShift(wrangle_id(words[0]), mkshift(lnum, words[2])),
-                "RSHIFT" => Gate::RShift(wrangle_id(words[0]), mkshift(lnum, words[2])),
-                huh => panic!("line {}: bad binary operator {:?}", lnum, huh)
-            },
-            _ => unreachable!()
-        };
+            }
+        }
+    };
+    s.expect("->")?;
+    Ok((expr, s.word()?.to_owned()))
+}
+
+pub fn parse(input: Input) -> Result<Vec<Insn>, LineError> {
+    let mut insns = Vec::new();
+    let mut fixups: usize = 0;
+    for (expr, dst) in input.scan_lines(gate)? {
         // Let's pretend this justifies making ExprMap so excitingly generic:
         let gate: Gate<String> = expr.idmap(|r| match *r {
             Ok(ref id) => id.clone(),
@@ -60,49 +85,77 @@ pub fn parse<B: BufRead>(input: B) -> Vec<Insn> {
         });
         insns.push((gate, dst));
     }
-    insns
+    Ok(insns)
 }
 
 #[cfg(test)]
 mod test {
     use super::parse;
     use ast::Gate;
+    use util::Input;
 
     fn s(s: &str) -> String { s.to_owned() }
 
     #[test]
     fn examples() {
-        assert_eq!(parse("123 -> x\n".as_bytes()),
+        assert_eq!(parse(Input::text("123 -> x\n")).unwrap(),
                    vec![(Gate::Imm(123), s("x"))]);
-        assert_eq!(parse("x AND y -> z\n".as_bytes()),
+        assert_eq!(parse(Input::text("x AND y -> z\n")).unwrap(),
                    vec![(Gate::And(s("x"), s("y")), s("z"))]);
-        assert_eq!(parse("x OR y -> z\n".as_bytes()),
+        assert_eq!(parse(Input::text("x OR y -> z\n")).unwrap(),
                    vec![(Gate::Or(s("x"), s("y")), s("z"))]);
-        assert_eq!(parse("p LSHIFT 2 -> q\n".as_bytes()),
+        assert_eq!(parse(Input::text("p LSHIFT 2 -> q\n")).unwrap(),
                    vec![(Gate::lshift(s("p"), 2), s("q"))]);
-        assert_eq!(parse("p RSHIFT 2 -> q\n".as_bytes()),
+        assert_eq!(parse(Input::text("p RSHIFT 2 -> q\n")).unwrap(),
                    vec![(Gate::rshift(s("p"), 2), s("q"))]);
-        assert_eq!(parse("NOT e -> f\n".as_bytes()),
+        assert_eq!(parse(Input::text("NOT e -> f\n")).unwrap(),
                    vec![(Gate::Not(s("e")), s("f"))]);
     }
 
+    #[test]
+    fn extensions() {
+        assert_eq!(parse(Input::text("x XOR y -> z\nx ADD y -> z\nx SUB y -> z\n")).unwrap(),
+                   vec![(Gate::Xor(s("x"), s("y")), s("z")),
+                        (Gate::Add(s("x"), s("y")), s("z")),
+                        (Gate::Sub(s("x"), s("y")), s("z"))]);
+        assert_eq!(parse(Input::text("MUX s a b -> z\n")).unwrap(),
+                   vec![(Gate::Mux(s("s"), s("a"), s("b")), s("z"))]);
+        assert_eq!(parse(Input::text("p LSHIFT q -> r\np RSHIFT q -> r\n")).unwrap(),
+                   vec![(Gate::LShiftBy(s("p"), s("q")), s("r")),
+                        (Gate::RShiftBy(s("p"), s("q")), s("r"))]);
+        assert_eq!(parse(Input::text("MUX 1 a 2 -> z\n")).unwrap(),
+                   vec![(Gate::Imm(1), s(" __lit0")),
+                        (Gate::Imm(2), s(" __lit1")),
+                        (Gate::Mux(s(" __lit0"), s("a"), s(" __lit1")), s("z"))]);
+    }
+
     #[test]
     fn lolhax() {
-        assert_eq!(parse("x -> y".as_bytes()),
+        assert_eq!(parse(Input::text("x -> y")).unwrap(),
                    vec![(Gate::Or(s("x"), s("x")), s("y"))]);
-        assert_eq!(parse("1 AND p -> q".as_bytes()),
+        assert_eq!(parse(Input::text("1 AND p -> q")).unwrap(),
                    vec![(Gate::Imm(1), s(" __lit0")),
                         (Gate::And(s(" __lit0"), s("p")), s("q"))]);
     }
 
     #[test]
     fn hax2() {
-        assert_eq!(parse("1 AND p -> q\n1 AND m -> n".as_bytes()),
+        assert_eq!(parse(Input::text("1 AND p -> q\n1 AND m -> n")).unwrap(),
                    vec![(Gate::Imm(1), s(" __lit0")),
                         (Gate::And(s(" __lit0"), s("p")), s("q")),
                         (Gate::Imm(1), s(" __lit1")),
                         (Gate::And(s(" __lit1"), s("m")), s("n"))]);
     }
 
-    // I could test the error cases... but do I even care at this point?
+    #[test]
+    fn errors() {
+        let err = |text: &str| parse(Input::text(text)).unwrap_err().to_string();
+        assert_eq!(err("x -> y\nx NAND y -> z"),
+                   "line 2, column 3: expected \"->\", found \"NAND\"");
+        assert_eq!(err("MUX s a -> z"), "line 1, column 9: expected a word, found \"->\"");
+        assert_eq!(err("p LSHIFT 16 -> q"), "line 1, column 10: 16 is out of range 0..=15");
+        assert_eq!(err("NOT -> f"), "line 1, column 5: expected a word, found \"->\"");
+        assert_eq!(err("x AND y -> z w"),
+                   "line 1, column 14: unexpected text \"w\" at end of line");
+    }
 }
@@ -1,6 +1,10 @@
 use ast::{Signal,Gate};
 use eager::{Eager,CheckedEager,EagerError,NoError};
 use lazy::{Lazy,UnsafeLazy,LazyError};
+use topo::{Topo,TopoError};
+use incr::Incremental;
+use opt::{optimize,Report};
+use dot::dot;
 use generic::{Linker,LinkerError,Eval,Program,ProgramT,Strategy,Decl};
 
 pub type Insn = (Gate<String>, String);
@@ -12,27 +16,65 @@ macro_rules! make_eval { {$name:ident<$Hkt:ident> -> $Err:ty} => {
     }
 }}
 
-type Prog = Program<Gate<Decl>, String>;
+pub type Prog = Program<Gate<Decl>, String>;
 
-fn gen_eval<S: for<'p> Strategy<'p, Prog>>(strat: S, insns: Vec<Insn>, outputs: &[&str])
-                                           -> Result<Vec<Signal>, Error<S::Error>> {
+fn link(insns: Vec<Insn>, outputs: &[&str]) -> Result<Prog, LinkerError<String>> {
     let mut ld = Linker::new();
     for (gate, out) in insns {
-        try!(ld.define(&out, gate));
-    }
-    let prog = try!(ld.link(outputs));
-    let eval = strat.load(&prog);
-    let mut sigs = Vec::new();
-    for &entry in prog.entries() {
-        sigs.push(try!(eval.run(entry).map_err(Error::EvalError)));
+        ld.define(&out, gate)?;
     }
-    Ok(sigs)
+    ld.link(outputs)
+}
+
+fn gen_eval<S: for<'p> Strategy<'p, Prog>>(strat: S, insns: Vec<Insn>, outputs: &[&str])
+                                           -> Result<Vec<Signal>, Error<S::Error>> {
+    let prog = try!(link(insns, outputs));
+    run(strat, &prog).map_err(Error::EvalError)
+}
+
+fn run<S: for<'p> Strategy<'p, Prog>>(strat: S, prog: &Prog) -> Result<Vec<Signal>, S::Error> {
+    let eval = strat.load(prog);
+    prog.entries().iter().map(|&entry| eval.run(entry)).collect()
 }
 
 make_eval!{eval_eager<Eager> -> NoError}
 make_eval!{eval_eager_checked<CheckedEager> -> EagerError<String>}
 make_eval!{eval_lazy<Lazy> -> LazyError<String>}
 make_eval!{eval_lazy_unsafe<UnsafeLazy> -> LazyError<String>}
+make_eval!{eval_topo<Topo> -> TopoError<String>}
+
+pub type Circuit = Incremental<Prog>;
+
+// For evaluating over and over with different overrides.
+pub fn circuit(insns: Vec<Insn>, outputs: &[&str]) -> Result<Circuit, LinkerError<String>> {
+    link(insns, outputs).map(Incremental::new)
+}
+
+pub fn optimized(insns: Vec<Insn>, outputs: &[&str])
+                 -> Result<(Prog, Report), LinkerError<String>> {
+    link(insns, outputs).map(|prog| optimize(&prog))
+}
+
+pub fn eval_optimized(insns: Vec<Insn>, outputs: &[&str])
+                      -> Result<(Vec<Signal>, Report), Error<TopoError<String>>> {
+    let (prog, report) = optimized(insns, outputs)?;
+    let sigs = run(Topo, &prog).map_err(Error::EvalError)?;
+    Ok((sigs, report))
+}
+
+// Every wire's value, or `None` for those stuck behind a cycle.
+pub fn signals(prog: &Prog) -> Vec<Option<Signal>> {
+    let eval = Topo.load(prog);
+    Decl::all(prog.len()).map(|d| eval.run(d).ok()).collect()
+}
+
+// The circuit as a Graphviz graph, with the values on if `with_signals`.
+pub fn graph(insns: Vec<Insn>, outputs: &[&str], with_signals: bool)
+             -> Result<String, LinkerError<String>> {
+    let prog = link(insns, outputs)?;
+    let sigs = if with_signals { Some(signals(&prog)) } else { None };
+    Ok(dot(&prog, sigs.as_ref().map(|s| &s[..])))
+}
 
 #[derive(Debug)]
 pub enum Error<EvalError> {
@@ -45,8 +87,11 @@ impl<E> From<LinkerError<String>> for Error<E> {
 
 #[cfg(test)]
 mod test {
-    use super::{eval_eager, eval_eager_checked, eval_lazy, eval_lazy_unsafe};
+    use super::{eval_eager, eval_eager_checked, eval_lazy, eval_lazy_unsafe, eval_topo};
+    use super::{circuit, eval_optimized, Error};
     use ast::{Gate};
+    use parse::parse;
+    use util::Input;
 
     fn s(s: &str) -> String { s.to_owned() }
 
@@ -103,6 +148,76 @@ mod test {
                    vec![0xfedc]);
     }
 
+    #[test]
+    fn topo_yes() {
+        assert_eq!(eval_topo(vec![(Gate::Imm(0x0123), s("a")),
+                                  (Gate::Not(s("a")), s("b"))], &["b"]).unwrap(),
+                   vec![0xfedc]);
+        assert_eq!(eval_topo(vec![(Gate::Not(s("a")), s("b")),
+                                  (Gate::Imm(0x0123), s("a"))], &["b", "a"]).unwrap(),
+                   vec![0xfedc, 0x0123]);
+        assert_eq!(eval_topo(vec![(Gate::Imm(123), s("x")),
+                                  (Gate::Imm(456), s("y")),
+                                  (Gate::And(s("x"), s("y")), s("d")),
+                                  (Gate::Or(s("x"), s("y")), s("e")),
+                                  (Gate::lshift(s("x"), 2), s("f")),
+                                  (Gate::rshift(s("y"), 2), s("g")),
+                                  (Gate::Not(s("x")), s("h")),
+                                  (Gate::Not(s("y")), s("i"))],
+                             &["d", "e", "f", "g", "h", "i", "x", "y"]).unwrap(),
+                   vec![72, 507, 492, 114, 65412, 65079, 123, 456]);
+    }
+
+    #[test]
+    fn topo_deep() {
+        let n = 200000;
+        let mut insns: Vec<_> = (1..n)
+            .map(|i| (Gate::Not(format!("w{}", i - 1)), format!("w{}", i)))
+            .collect();
+        insns.push((Gate::Imm(1), s("w0")));
+        let last = format!("w{}", n - 1);
+        assert_eq!(eval_topo(insns, &[&last]).unwrap(), vec![0xfffe]);
+    }
+
+    #[test]
+    fn topo_cycles() {
+        let insns = || vec![(Gate::Imm(1), s("one")),
+                            (Gate::And(s("one"), s("c")), s("a")),
+                            (Gate::Not(s("a")), s("b")),
+                            (Gate::Or(s("b"), s("one")), s("c")),
+                            (Gate::Not(s("c")), s("d")),
+                            (Gate::Not(s("e")), s("e")),
+                            (Gate::And(s("d"), s("e")), s("f"))];
+        let err = |out| format!("{:?}", eval_topo(insns(), &[out]).unwrap_err());
+        assert_eq!(err("a"), "EvalError(Cycle([\"a\", \"c\", \"b\"]))");
+        assert_eq!(err("d"), "EvalError(Cycle([\"a\", \"c\", \"b\"]))");
+        assert_eq!(err("e"), "EvalError(Cycle([\"e\"]))");
+        assert_eq!(err("f"), "EvalError(Cycle([\"a\", \"c\", \"b\", \"e\"]))");
+        assert_eq!(eval_topo(insns(), &["one"]).unwrap(), vec![1]);
+        match eval_topo(insns(), &["f"]).unwrap_err() {
+            Error::EvalError(err) => assert_eq!(err.to_string(),
+                                                "stuck behind a cycle through a c b e"),
+            err => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn extended() {
+        let insns = || parse(Input::text(
+            "4660 -> x\n255 -> y\nx XOR y -> a\nx ADD y -> b\ny SUB x -> c\n\
+             MUX y x 5 -> d\nMUX 0 x 5 -> e\n4 -> n\nx LSHIFT n -> f\n\
+             x RSHIFT n -> g\nx LSHIFT y -> h\nx RSHIFT 4 -> i\n")).unwrap();
+        let outs = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+        let want = vec![0x12cb, 0x1333, 0xeecb, 0x1234, 5, 0x2340, 0x0123, 0, 0x0123];
+        assert_eq!(eval_eager(insns(), &outs).unwrap(), want);
+        assert_eq!(eval_eager_checked(insns(), &outs).unwrap(), want);
+        assert_eq!(eval_lazy(insns(), &outs).unwrap(), want);
+        assert_eq!(eval_lazy_unsafe(insns(), &outs).unwrap(), want);
+        assert_eq!(eval_topo(insns(), &outs).unwrap(), want);
+        assert_eq!(circuit(insns(), &outs).unwrap().outputs().unwrap(), want);
+        assert_eq!(eval_optimized(insns(), &outs).unwrap().0, want);
+    }
+
     // TODO/FIXME/XXX/etc.: Write some tests for the error cases.  I
     // wrote that code, so it might as well do something.  (Except the
     // error case for the unchecked eager evaluator hitting a cycle,
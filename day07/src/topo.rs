@@ -0,0 +1,181 @@
+use generic::{Eval,Expr,Decl,ProgramT,ProgResult,Strategy,Erroneous};
+use std::cmp::min;
+use std::fmt;
+
+// Sorts the whole program up front, then evaluates each declaration
+// exactly once, in order; nothing recurses, so long chains of wires are
+// fine.  Anything in a cycle, or depending on one, is left without a
+// value.
+pub struct Topo;
+impl<P: ProgramT> Erroneous<P> for Topo {
+    type Error = TopoError<P::OuterIdent>;
+}
+impl<'p, P: ProgramT + 'p> Strategy<'p, P> for Topo
+    where <P::Expr as Expr>::Value: Clone {
+    type Eval = TopoEval<'p, P>;
+    fn load(&self, prog: &'p P) -> Self::Eval {
+        let decls: Vec<Decl> = Decl::all(prog.len()).collect();
+        let deps = deps(prog);
+        let mut values = vec![None; decls.len()];
+        let mut cyclic = vec![false; decls.len()];
+        for comp in components(&deps) {
+            let i = comp[0];
+            if is_cycle(&deps, &comp) {
+                for &j in &comp {
+                    cyclic[j] = true;
+                }
+                continue;
+            }
+            // Everything this uses is done by now, unless it's stuck
+            // behind a cycle, in which case so is this.
+            let value = prog.lookup(decls[i]).eval(|d| values[d.get()].clone().ok_or(()));
+            values[i] = value.ok();
+        }
+        TopoEval { prog, deps, values, cyclic }
+    }
+}
+pub struct TopoEval<'p, P: ProgramT + 'p> {
+    prog: &'p P,
+    deps: Vec<Vec<usize>>,
+    values: Vec<Option<<P::Expr as Expr>::Value>>,
+    cyclic: Vec<bool>,
+}
+impl<'p, P: ProgramT + 'p> Eval<'p, P, TopoError<P::OuterIdent>> for TopoEval<'p, P>
+    where <P::Expr as Expr>::Value: Clone {
+    fn run(&self, entry: Decl) -> ProgResult<P, TopoError<P::OuterIdent>> {
+        match self.values[entry.get()] {
+            Some(ref value) => Ok(value.clone()),
+            None => Err(TopoError::Cycle(self.cycles_under(entry))),
+        }
+    }
+}
+impl<'p, P: ProgramT> TopoEval<'p, P> {
+    fn cycles_under(&self, entry: Decl) -> Vec<P::OuterIdent> {
+        cycles_under(&self.deps, &self.cyclic, entry).into_iter()
+            .map(|d| self.prog.debug(d))
+            .collect()
+    }
+}
+#[derive(Debug)]
+pub enum TopoError<Ident> {
+    Cycle(Vec<Ident>)
+}
+impl<Ident: fmt::Display> fmt::Display for TopoError<Ident> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TopoError::Cycle(ref wires) => {
+                write!(f, "stuck behind a cycle through")?;
+                for w in wires {
+                    write!(f, " {}", w)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// What each declaration uses, by index.
+pub fn deps<P: ProgramT>(prog: &P) -> Vec<Vec<usize>> {
+    Decl::all(prog.len()).map(|d| {
+        let mut ds = Vec::new();
+        prog.lookup(d).deps(|dd| ds.push(dd.get()));
+        ds
+    }).collect()
+}
+
+// Whether a component from `components` is a cycle, or just one
+// declaration that doesn't use itself.
+pub fn is_cycle(deps: &[Vec<usize>], comp: &[usize]) -> bool {
+    comp.len() > 1 || deps[comp[0]].contains(&comp[0])
+}
+
+// Every declaration in every cycle that `entry` depends on, in the order
+// the linker first saw their names.
+pub fn cycles_under(deps: &[Vec<usize>], cyclic: &[bool], entry: Decl) -> Vec<Decl> {
+    let mut seen = vec![false; deps.len()];
+    let mut todo = vec![entry.get()];
+    seen[entry.get()] = true;
+    while let Some(i) = todo.pop() {
+        for &j in &deps[i] {
+            if !seen[j] {
+                seen[j] = true;
+                todo.push(j);
+            }
+        }
+    }
+    Decl::all(deps.len()).filter(|d| seen[d.get()] && cyclic[d.get()]).collect()
+}
+
+// Tarjan's strongly connected components, with an explicit stack.  They
+// come out dependencies first, so that's the order to evaluate in.
+pub fn components(deps: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = deps.len();
+    let mut t = Tarjan {
+        deps,
+        index: vec![UNSEEN; n],
+        low: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        work: Vec::new(),
+        next: 0,
+    };
+    let mut comps = Vec::new();
+    for root in 0..n {
+        if t.index[root] == UNSEEN {
+            t.enter(root);
+            t.run(&mut comps);
+        }
+    }
+    comps
+}
+
+const UNSEEN: usize = !0;
+
+struct Tarjan<'a> {
+    deps: &'a [Vec<usize>],
+    index: Vec<usize>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    // Each node being visited, and how many of its deps have been seen to.
+    work: Vec<(usize, usize)>,
+    next: usize,
+}
+impl<'a> Tarjan<'a> {
+    fn enter(&mut self, v: usize) {
+        self.index[v] = self.next;
+        self.low[v] = self.next;
+        self.next += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+        self.work.push((v, 0));
+    }
+    fn run(&mut self, comps: &mut Vec<Vec<usize>>) {
+        while let Some((v, i)) = self.work.pop() {
+            if let Some(&w) = self.deps[v].get(i) {
+                self.work.push((v, i + 1));
+                if self.index[w] == UNSEEN {
+                    self.enter(w);
+                } else if self.on_stack[w] {
+                    self.low[v] = min(self.low[v], self.index[w]);
+                }
+                continue;
+            }
+            if self.low[v] == self.index[v] {
+                let mut comp = Vec::new();
+                loop {
+                    let w = self.stack.pop().unwrap();
+                    self.on_stack[w] = false;
+                    comp.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                comps.push(comp);
+            }
+            if let Some(&(u, _)) = self.work.last() {
+                self.low[u] = min(self.low[u], self.low[v]);
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+extern crate util;
+use util::best::{Smallest,Largest,Cmp,Counts};
+use util::stack_set::BitSet;
+use util::solution::{json_mode, Answer, Report, Solution};
+use util::symtab::Id;
+use util::{search,Json};
+
+mod reader;
+
+use std::error::Error;
+use std::io::{stdin, BufRead};
+
+type Dist = usize;
+type Grid = reader::Grid<Dist>;
+type Problem = (reader::SymTab, Grid);
+
+// The most hopeful guess at any one leg of the trip, for pruning:
+// every leg left to go can't be better than this.
+trait Hopeful: Cmp<Dist> {
+    fn leg(&self, g: &Grid) -> Dist;
+    // Searches only ever look for the smallest cost, so this is what a
+    // leg `d` long costs, given the longest road there is.
+    fn cost(&self, d: Dist, longest: Dist) -> Dist;
+}
+impl Hopeful for Smallest {
+    fn leg(&self, g: &Grid) -> Dist {
+        g.iter().flat_map(|row| row.iter().filter_map(|&d| d)).min().unwrap_or(0)
+    }
+    fn cost(&self, d: Dist, _longest: Dist) -> Dist {
+        d
+    }
+}
+impl Hopeful for Largest {
+    fn leg(&self, g: &Grid) -> Dist {
+        g.iter().flat_map(|row| row.iter().filter_map(|&d| d)).max().unwrap_or(0)
+    }
+    // Every trip has the same number of legs, so the one that's least
+    // short of the longest road on every leg is the longest trip.
+    fn cost(&self, d: Dist, longest: Dist) -> Dist {
+        longest - d
+    }
+}
+
+fn road(g: &Grid, i: usize, j: usize) -> Option<Dist> {
+    g[i][j]
+}
+
+fn parse<B: BufRead>(input: B) -> Problem {
+    let mut stab = reader::SymTab::default();
+    let g = reader::parse(&mut stab, input);
+    (stab, g)
+}
+
+// The trip as a search problem: the state is the places visited so far
+// and the one it's at (if it's started yet).  The set grows with the
+// map, so there's no limit on how many places there can be.
+type Visit = (Vec<u64>, Option<usize>);
+
+struct Trip<'a, C> {
+    g: &'a Grid,
+    cmp: C,
+    longest: Dist,
+    hope: Dist,
+}
+
+impl<'a, C: Hopeful> search::Problem for Trip<'a, C> {
+    type State = Visit;
+    type Step = usize;
+    type Cost = Dist;
+
+    fn start(&self) -> Visit {
+        (BitSet::new(self.g.len()).unwrap(), None)
+    }
+    fn successors(&self, &(ref seen, at): &Visit) -> Vec<(usize, Visit, Dist)> {
+        (0..self.g.len()).filter(|&j| !seen.contains(j)).filter_map(|j| {
+            let leg = match at {
+                None => 0,
+                Some(i) => self.cmp.cost(road(self.g, i, j)?, self.longest),
+            };
+            let mut seen = seen.clone();
+            seen.set(j);
+            Some((j, (seen, Some(j)), leg))
+        }).collect()
+    }
+    fn is_goal(&self, visit: &Visit) -> bool {
+        visit.0.is_full()
+    }
+    fn heuristic(&self, &(ref seen, at): &Visit) -> Dist {
+        // Bits past the end start out set, so only the real places are
+        // left clear.
+        let left = seen.iter().map(|w| w.count_zeros() as usize).sum::<usize>();
+        match at {
+            None => left.saturating_sub(1) * self.hope,
+            Some(_) => left * self.hope,
+        }
+    }
+}
+
+// The route, and how the search went.
+fn compute<C: Hopeful + Sync>(p: &Problem, cmp: C) -> (Dist, Vec<&str>, Counts) {
+    let &(ref stab, ref g) = p;
+    let longest = Largest.leg(g);
+    let hope = cmp.cost(cmp.leg(g), longest);
+    let trip = Trip { g, cmp, longest, hope };
+    let (found, counts) = search::iddfs_counted(&trip, g.len(), 1);
+    let path = found.expect("No path!?").path;
+    let dist = path.windows(2).map(|w| road(g, w[0], w[1]).unwrap()).sum();
+    (dist, path.iter().map(|i| stab.name(Id::from_index(*i))).collect(), counts)
+}
+
+pub fn main() {
+    json_mode(9, &Day);
+    let stdin = stdin();
+    let prob = parse(stdin.lock());
+    let (sdist, splaces, scounts) = compute(&prob, Smallest);
+    println!("Smallest: {}; {}", sdist, splaces.join(" -> "));
+    eprintln!("(search: {})", scounts);
+    let (ldist, lplaces, lcounts) = compute(&prob, Largest);
+    println!("Largest: {}; {}", ldist, lplaces.join(" -> "));
+    eprintln!("(search: {})", lcounts);
+}
+
+// The witness is the route itself.
+fn trip<C: Hopeful + Sync>(input: &str, cmp: C) -> Report {
+    let prob = parse(input.as_bytes());
+    let (dist, places, _) = compute(&prob, cmp);
+    Report::with_witness(dist.to_string(), Json::obj(vec![
+        ("route", Json::from(places)),
+        ("distance", Json::from(dist)),
+    ]))
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        Ok(trip(input, Smallest).answer)
+    }
+    fn part2(&self, input: &str) -> Answer {
+        Ok(trip(input, Largest).answer)
+    }
+    fn report(&self, n: u32, input: &str) -> Result<Report, Box<dyn Error>> {
+        match n {
+            1 => Ok(trip(input, Smallest)),
+            2 => Ok(trip(input, Largest)),
+            _ => self.part(n, input).map(Report::new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use util::best::{Smallest,Largest};
+    use util::solution::Solution;
+    use super::{compute,parse,Day};
+
+    const EXAMPLE: &'static str = "\
+        London to Dublin = 464\n\
+        London to Belfast = 518\n\
+        Dublin to Belfast = 141\n";
+
+    #[test]
+    fn example() {
+        let prob = parse(EXAMPLE.as_bytes());
+        let (dist, path, _) = compute(&prob, Smallest);
+        assert_eq!(dist, 605);
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0], "London");
+        assert_eq!(path[1], "Dublin");
+        assert_eq!(path[2], "Belfast");
+    }
+
+    #[test]
+    fn example_long() {
+        let prob = parse(EXAMPLE.as_bytes());
+        let (dist, path, _) = compute(&prob, Largest);
+        assert_eq!(dist, 982);
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0], "Dublin");
+        assert_eq!(path[1], "London");
+        assert_eq!(path[2], "Belfast");
+    }
+
+    // More places than fit in a machine word: a long road with a town
+    // every mile, and nothing else.
+    #[test]
+    fn many_places() {
+        let text: String = (1..100).map(|i| format!("T{} to T{} = 1\n", i - 1, i)).collect();
+        let prob = parse(text.as_bytes());
+        let (dist, path, _) = compute(&prob, Smallest);
+        assert_eq!(dist, 99);
+        assert_eq!(path.len(), 100);
+        assert!(path[0] == "T0" || path[0] == "T99", "{:?}", path);
+    }
+
+    #[test]
+    fn report() {
+        let report = Day.report(1, EXAMPLE).unwrap();
+        assert_eq!(report.answer, "605");
+        assert_eq!(report.witness.unwrap().to_string(),
+                   "{\"route\":[\"London\",\"Dublin\",\"Belfast\"],\"distance\":605}");
+    }
+}
@@ -1,16 +1,19 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::io::{BufRead, Read};
 use std::str::FromStr;
 
-use util::{AutoVec,WriteOnce};
+use util::{AutoGrid,WriteOnce};
+use util::symtab::Id;
+use util::write_once::Policy;
 
-pub use util::SymTab; // FIXME
-pub type Grid<N> = Box<[Box<[Option<N>]>]>;
+pub enum City {}
+pub type SymTab = ::util::SymTab<Id<City>>;
+pub type Grid<N> = Box<[Box<[Option<N>]>]>; // will be fully square
 
-type GridAcc<N> = AutoVec<AutoVec<WriteOnce<N>>>;
+type GridAcc<N> = AutoGrid<WriteOnce<N>>;
 
 pub fn parse<N, B>(stab: &mut SymTab, b: B) -> Grid<N>
-    where N: FromStr + Copy, N::Err: Debug, B: BufRead {
+    where N: FromStr + Copy + PartialEq + Display, N::Err: Debug, B: BufRead {
     let mut acc: GridAcc<N> = Default::default();
     for (ln, line) in b.lines().enumerate() {
         let line = line.expect("I/O error");
@@ -26,13 +29,15 @@ pub fn parse<N, B>(stab: &mut SymTab, b: B) -> Grid<N>
         let dist = N::from_str(words[4]).unwrap_or_else(|e| {
             panic!("line {}: {:?} is not a number: {:?}", ln, words[4], e);
         });
-        acc.at(nsrc).at(ndst).set(dist).unwrap_or_else(|_v| {
-            panic!("line {}: duplicate distance from {} to {}", ln, words[0], words[2]);
+        // Saying the same thing twice (either way around) is harmless.
+        acc.at(nsrc.index(), ndst.index()).set_with(dist, Policy::AcceptEqual).unwrap_or_else(|c| {
+            panic!("line {}: distance {}->{} given twice ({})", ln, words[0], words[2], c);
         });
-        acc.at(ndst).at(nsrc).set(dist).unwrap_or_else(|_v| unreachable!());
+        acc.at(ndst.index(), nsrc.index()).set_with(dist, Policy::AcceptEqual)
+            .unwrap_or_else(|_c| unreachable!());
     }
-    acc.into_iter().map(|row| {
-        row.into_iter().map(|cell| {
+    acc.into_square().into_vec().into_iter().map(|row| {
+        row.into_vec().into_iter().map(|cell| {
             cell.into_inner()
         }).collect::<Vec<_>>().into_boxed_slice()
     }).collect::<Vec<_>>().into_boxed_slice()
@@ -40,19 +45,39 @@ pub fn parse<N, B>(stab: &mut SymTab, b: B) -> Grid<N>
 
 #[cfg(test)]
 mod test {
+    use util::symtab::Id;
     use super::{parse, Grid, SymTab};
 
     #[test]
     fn parse_simple() {
-        let mut st = SymTab::new();
+        let mut st = SymTab::default();
         let gr: Grid<usize> = parse(&mut st, "Coals to Newcastle = 31337\n".as_bytes());
         assert_eq!(st.len(), 2);
-        assert_eq!(st.print(0), "Coals");
-        assert_eq!(st.print(1), "Newcastle");
+        assert_eq!(st.print(Id::from_index(0)), "Coals");
+        assert_eq!(st.print(Id::from_index(1)), "Newcastle");
         assert_eq!(gr[0][1], Some(31337));
         assert_eq!(gr[1][0], Some(31337));
         assert_eq!(gr[0][0], None);
+        assert_eq!(gr[1][1], None);
+        assert_eq!(gr.len(), 2);
         assert_eq!(gr[0].len(), 2);
-        assert_eq!(gr[1].len(), 1);
+        assert_eq!(gr[1].len(), 2);
+    }
+
+    #[test]
+    fn repeated() {
+        let mut st = SymTab::default();
+        let gr: Grid<usize> = parse(&mut st, "London to Dublin = 464\n\
+                                               Dublin to London = 464\n".as_bytes());
+        assert_eq!(gr[0][1], Some(464));
+        assert_eq!(gr[1][0], Some(464));
+    }
+
+    #[test]
+    #[should_panic(expected = "distance London->Dublin given twice (464 vs 465)")]
+    fn conflicting() {
+        let mut st = SymTab::default();
+        let _: Grid<usize> = parse(&mut st, "London to Dublin = 464\n\
+                                             London to Dublin = 465\n".as_bytes());
     }
 }
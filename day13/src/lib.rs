@@ -0,0 +1,128 @@
+extern crate util;
+mod parser;
+
+use util::{par,Input,Walk};
+use util::best::{Counts,Largest};
+use util::input::LineError;
+use util::solution::{json_mode, Answer, Solution};
+use util::symtab::Id;
+use parser::{Grid,Points,SymTab};
+
+type Bits = Vec<u64>;
+type Best = util::Best<Points, Vec<usize>, Largest>;
+type Problem = (SymTab, Grid);
+
+// Still a lot like day09, but the table is a circle:
+fn compute(prob: &Problem) -> (Points, Vec<&str>, Counts) {
+    let &(ref stab, ref grid) = prob;
+    let n = grid.len();
+    let best_pair = grid.iter().flat_map(|row| row.iter().cloned()).max().unwrap_or(0);
+    // The semantics of the dinner table are invariant under rotation, so
+    // w.l.o.g. seat the first person first; then each thread takes
+    // whoever's next to them.
+    let keep = |path: &[usize]| path[0] == 0;
+    // Handles one step of the walk, given the happiness so far for each
+    // prefix of the path before it; false means prune.
+    let step = |so_far: &mut Vec<Points>, path: &[usize], be: &mut Best| {
+        let l = path.len();
+        so_far.truncate(l - 1);
+        let here = match l {
+            1 => 0,
+            _ => so_far[l - 2] + grid[path[l - 2]][path[l - 1]],
+        };
+        so_far.push(here);
+        // Every pair left to seat, including closing the circle, is at
+        // best the happiest pair at the table.
+        let pairs_left = (n - l + 1) as Points;
+        if !be.can_win(here + pairs_left * best_pair) {
+            return false;
+        }
+        if l == n {
+            if l < 3 {
+                unimplemented!();
+            }
+            let close = grid[path[l - 1]][path[0]];
+            be.add(here + close, path);
+        }
+        true
+    };
+    let seconds = par::prefixes::<Bits, _>(n, 2, keep);
+    let be = par::search(&seconds, Best::new(Largest), |start, be| {
+        let mut so_far: Vec<Points> = Vec::with_capacity(n);
+        if !(1..=start.len()).all(|l| step(&mut so_far, &start[..l], be)) {
+            return;
+        }
+        let mut walk = Walk::<Bits, _>::within(n, start, keep).expect("too many people");
+        while let Some(path) = walk.next() {
+            if !step(&mut so_far, &path, be) {
+                walk.prune();
+            }
+        }
+    });
+    let counts = be.counts();
+    let (points, order) = be.unwrap();
+    (points, order.iter().map(|i| stab.name(Id::from_index(*i))).collect(), counts)
+}
+
+pub fn main() {
+    json_mode(13, &Day);
+    let prob = parser::parse(Input::stdin()).unwrap_or_else(|err| panic!("{}", err));
+    let (points, order, counts) = compute(&prob);
+    println!("Δhappiness = {}", points);
+    println!("Order: {}.", order.join(", "));
+    eprintln!("(search: {})", counts);
+}
+
+// For part 2: one more guest, who's indifferent to everyone else and
+// vice versa.
+fn with_guest(prob: &Problem, name: &str) -> Problem {
+    let &(ref stab, ref grid) = prob;
+    let mut stab = stab.clone();
+    stab.read(name);
+    let n = grid.len() + 1;
+    let grid = (0..n).map(|i| {
+        (0..n).map(|j| {
+            grid.get(i).and_then(|row| row.get(j)).cloned().unwrap_or(0)
+        }).collect::<Vec<_>>().into_boxed_slice()
+    }).collect::<Vec<_>>().into_boxed_slice();
+    (stab, grid)
+}
+
+fn parse_text(input: &str) -> Result<Problem, LineError> {
+    parser::parse(Input::text(input))
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        Ok(compute(&parse_text(input)?).0.to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        Ok(compute(&with_guest(&parse_text(input)?, "Yourself")).0.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute, parse_text};
+
+    #[test]
+    fn example() {
+        let text = "\
+        Alice would gain 54 happiness units by sitting next to Bob.\n\
+        Alice would lose 79 happiness units by sitting next to Carol.\n\
+        Alice would lose 2 happiness units by sitting next to David.\n\
+        Bob would gain 83 happiness units by sitting next to Alice.\n\
+        Bob would lose 7 happiness units by sitting next to Carol.\n\
+        Bob would lose 63 happiness units by sitting next to David.\n\
+        Carol would lose 62 happiness units by sitting next to Alice.\n\
+        Carol would gain 60 happiness units by sitting next to Bob.\n\
+        Carol would gain 55 happiness units by sitting next to David.\n\
+        David would gain 46 happiness units by sitting next to Alice.\n\
+        David would lose 7 happiness units by sitting next to Bob.\n\
+        David would gain 41 happiness units by sitting next to Carol.";
+        let prob = parse_text(text).unwrap();
+        let (score, _order, _) = compute(&prob);
+        assert_eq!(score, 330);
+    }
+}
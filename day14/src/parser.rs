@@ -1,56 +1,50 @@
-use regex::Regex;
-use types::{Speed,Time};
+use types::Time;
 use ::Reindeer;
 
-use std::io;
-use std::num::ParseIntError;
-use std::str::FromStr;
+use util::input::LineError;
+use util::scan::Scanner;
 
-#[derive(Debug)]
-pub enum Error {
-    SyntaxError,
-    IntError(ParseIntError),
-    IOError(io::Error),
+fn duration(s: &mut Scanner) -> Result<Time, LineError> {
+    let t = s.int()?;
+    s.one_of(&[("seconds", ()), ("second", ())])?;
+    Ok(t)
 }
-// Sigh.
-macro_rules! impl_from{{ $outer:ident: $($v:ident($inner:ty)),* } => {
-    $(impl From<$inner> for $outer { fn from(x: $inner) -> $outer { $outer::$v(x) }})*
-}}
-impl_from!{ Error: IntError(ParseIntError), IOError(io::Error) }
 
-pub struct Parser(Regex);
-impl Parser {
-    pub fn new() -> Parser {
-        Parser(Regex::new(r"(?P<name>\S+) can fly (?P<speed>\d+) km/s for (?P<burst>\d+) (?x)
-                       (?-x)seconds?, but then must rest for (?P<rest>\d+) seconds?\.").unwrap())
-    }
-    pub fn parse(&self, s: &str) -> Result<Reindeer,Error> {
-        let caps = try!(self.0.captures(s).ok_or(Error::SyntaxError));
-        let name = caps.name("name").unwrap().to_owned();
-        let speed = try!(Speed::from_str(caps.name("speed").unwrap()));
-        let burst = try!(Time::from_str(caps.name("burst").unwrap()));
-        let rest = try!(Time::from_str(caps.name("rest").unwrap()));
-        Ok(Reindeer{ name: name, speed: speed, burst: burst, rest: rest })
-    }
-    pub fn parse_all<B: io::BufRead>(&self, b: B) -> Vec<Result<Reindeer,Error>> {
-        b.lines().map(|line| {
-            let line = try!(line);
-            self.parse(&line)
-        }).collect()
-    }
+pub fn reindeer(s: &mut Scanner) -> Result<Reindeer, LineError> {
+    let name = s.word()?.to_owned();
+    s.expect("can fly")?;
+    let speed = s.int()?;
+    s.expect("km/s for")?;
+    let burst = duration(s)?;
+    s.expect(", but then must rest for")?;
+    let rest = duration(s)?;
+    s.expect(".")?;
+    Ok(Reindeer { name, speed, burst, rest })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Parser;
+    use util::scan::scan;
+    use super::reindeer;
     use ::tests::rd;
 
     #[test]
     fn examples() {
-        let p = Parser::new();
         let comet = "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.";
         let dancer = "Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.";
-        assert_eq!(p.parse(comet).unwrap(), rd("Comet", 14, 10, 127));
-        assert_eq!(p.parse(dancer).unwrap(), rd("Dancer", 16, 11, 162));
+        assert_eq!(scan(1, comet, reindeer).unwrap(), rd("Comet", 14, 10, 127));
+        assert_eq!(scan(1, dancer, reindeer).unwrap(), rd("Dancer", 16, 11, 162));
+        let rudolph = "Rudolph can fly 1 km/s for 1 second, but then must rest for 1 second.";
+        assert_eq!(scan(1, rudolph, reindeer).unwrap(), rd("Rudolph", 1, 1, 1));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |text| scan(1, text, reindeer).unwrap_err().to_string();
+        assert_eq!(err("Comet can fly 14 km/s for 10 minutes, but then must rest for 127 seconds."),
+                   "line 1, column 30: expected one of \"seconds\" or \"second\", \
+                    found \"minutes,\"");
+        assert_eq!(err("Comet can fly fast"),
+                   "line 1, column 15: bad number \"fast\": invalid digit found in string");
     }
 }
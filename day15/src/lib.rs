@@ -0,0 +1,101 @@
+extern crate util;
+mod parser;
+mod search;
+
+use std::cmp::max;
+use std::convert::From;
+use std::ops::{Add, Mul};
+
+use parser::parse;
+use search::exhaustive;
+use util::Input;
+use util::input::LineError;
+use util::solution::{json_mode, Answer, Solution};
+
+pub type Num = i64;
+pub type Qty = u8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub capacity: Num,
+    pub durability: Num,
+    pub flavor: Num,
+    pub texture: Num,
+    pub calories: Num,
+}
+impl Stats {
+    fn zero() -> Stats {
+        Stats { capacity: 0, durability: 0, flavor: 0, texture: 0, calories: 0 }
+    }
+    fn eval(&self) -> Num {
+        max(self.capacity, 0) *
+            max(self.durability, 0) *
+            max(self.flavor, 0) *
+            max(self.texture, 0)
+    }
+}
+impl Add<Stats> for Stats {
+    type Output = Stats;
+    fn add(self, other: Stats) -> Stats {
+        Stats {
+            // There ought to be a way to use macros here...
+            capacity: self.capacity + other.capacity,
+            durability: self.durability + other.durability,
+            flavor: self.flavor + other.flavor,
+            texture: self.texture + other.texture,
+            calories: self.calories + other.calories,
+        }
+    }
+}
+impl<N: Copy> Mul<N> for Stats where Num: From<N> {
+    type Output = Stats;
+    fn mul(self, other: N) -> Stats {
+        let n = Num::from(other);
+        Stats {
+            capacity: self.capacity * n,
+            durability: self.durability * n,
+            flavor: self.flavor * n,
+            texture: self.texture * n,
+            calories: self.calories * n,            
+        }
+    }
+}
+
+fn parse_all(input: Input) -> Result<(Vec<String>, Vec<Stats>), LineError> {
+    let parsed = input.parse_lines(|l| parse(l).map(|(name, ingr)| (name.to_owned(), ingr)))?;
+    Ok(parsed.into_iter().unzip())
+}
+
+pub fn main() {
+    json_mode(15, &Day);
+    let (names, ingrs) = parse_all(Input::stdin()).unwrap_or_else(|err| panic!("{}", err));
+    {
+        let ((score, soln), counts) = search::exhaustive_counted(&ingrs, 100, None);
+        println!("Maximal cookie score, freestyle: {}", score);
+        eprintln!("(search: {})", counts);
+        for (i, name) in names.iter().enumerate() {
+            println!("* {} tsp of {}", soln[i], name);
+        }
+    }
+    println!("");
+    {
+        let ((score, soln), counts) = search::exhaustive_counted(&ingrs, 100, Some(500));
+        println!("Maximal cookie score, calorie-counting: {}", score);
+        eprintln!("(search: {})", counts);
+        for (i, name) in names.iter().enumerate() {
+            println!("* {} tsp of {}", soln[i], name);
+        }
+    }
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        let (_, ingrs) = parse_all(Input::text(input))?;
+        Ok(exhaustive(&ingrs, 100, None).0.to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        let (_, ingrs) = parse_all(Input::text(input))?;
+        Ok(exhaustive(&ingrs, 100, Some(500)).0.to_string())
+    }
+}
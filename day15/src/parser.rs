@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::iter::IntoIterator;
 use std::str::FromStr;
 
@@ -12,6 +13,18 @@ pub enum Error {
     SpaceFail,
     NumberFail,
 }
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NumberFail => write!(f, "bad number"),
+            Error::SpaceFail => write!(f, "attribute must be name' 'value pair"),
+            Error::ColonFail => write!(f, "ingredient must be name':'stats pair"),
+            Error::MissingStat(name) => write!(f, "missing attribute {}", name),
+            Error::DupStat(ref name, v0, v1) => write!(f, "attribute {} given twice, \
+                                                           as {} and {}", name, v0, v1),
+        }
+    }
+}
 
 fn unpair<I: IntoIterator>(ii: I) -> Option<(I::Item, I::Item)> {
     let mut i = ii.into_iter();
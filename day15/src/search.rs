@@ -1,6 +1,7 @@
 use std::cmp::{min,max};
 
-use util::best;
+use util::{best,par};
+use util::best::Counts;
 use ::{Stats,Num,Qty};
 
 type Best = best::Best<Num, Vec<Qty>, best::Largest>;
@@ -44,16 +45,36 @@ impl CookieMonitor for CalorieCounter {
     }
 }
 
+// For each prefix of the ingredients, the best each attribute could
+// do per teaspoon; spending everything that's left on that is an
+// upper bound on how the rest of the recipe can turn out.
+fn prefix_max(stats: &[Stats]) -> Vec<Stats> {
+    stats.iter().scan(stats[0].clone(), |state, stat| {
+        *state = Stats {
+            capacity: max(state.capacity, stat.capacity),
+            durability: max(state.durability, stat.durability),
+            flavor: max(state.flavor, stat.flavor),
+            texture: max(state.texture, stat.texture),
+            calories: max(state.calories, stat.calories),
+        };
+        Some(state.clone())
+    }).collect()
+}
+
 struct ExhCtx<'s> {
     stats: &'s [Stats],
+    maxes: &'s [Stats],
     qtys: Vec<Qty>,
-    best: Best,
+    best: &'s mut Best,
 }
 
 fn exh_recur<M: CookieMonitor>(ctx: &mut ExhCtx, mon: &M, i: usize, left: Qty, acc: Stats) {
     if !mon.judge(i, left, &acc) {
         return;
     }
+    if !ctx.best.can_win((acc.clone() + ctx.maxes[i].clone() * left).eval()) {
+        return;
+    }
     if i == 0 {
         ctx.qtys[i] = left;
         ctx.best.add((acc + ctx.stats[i].clone() * left).eval(), &ctx.qtys);
@@ -65,22 +86,40 @@ fn exh_recur<M: CookieMonitor>(ctx: &mut ExhCtx, mon: &M, i: usize, left: Qty, a
     }
 }
 
+// Each thread takes some of the amounts of the last ingredient, and
+// searches the rest of the recipe under that.
+fn exh_par<M: CookieMonitor + Sync>(stats: &[Stats], total: Qty, mon: &M) -> Best {
+    let maxes = prefix_max(stats);
+    let n1 = stats.len() - 1;
+    let amounts: Vec<Qty> = (0..(total+1)).collect();
+    par::search(&amounts, Best::new(best::Largest), |&this, best| {
+        let mut ctx = ExhCtx { stats, maxes: &maxes, qtys: vec![!0; stats.len()], best };
+        if n1 == 0 {
+            if this == total {
+                exh_recur(&mut ctx, mon, 0, total, Stats::zero());
+            }
+        } else {
+            ctx.qtys[n1] = this;
+            let acc = Stats::zero() + stats[n1].clone() * this;
+            exh_recur(&mut ctx, mon, n1 - 1, total - this, acc);
+        }
+    })
+}
+
 pub fn exhaustive(stats: &[Stats], total: Qty, opt_cals: Option<Num>) -> (Num, Vec<Qty>) {
+    exhaustive_counted(stats, total, opt_cals).0
+}
+
+// The same, and how the branch and bound went.
+pub fn exhaustive_counted(stats: &[Stats], total: Qty, opt_cals: Option<Num>)
+                          -> ((Num, Vec<Qty>), Counts) {
     assert!(stats.len() >= 1);
-    let mut ctx = ExhCtx {
-        stats: stats,
-        qtys: vec![!0; stats.len()],
-        best: Best::new(best::Largest),
-    };
-    let n1 = stats.len() - 1;
-    match opt_cals {
-        None => exh_recur(&mut ctx, &Whatever, n1, total, Stats::zero()),
-        Some(cals) => {
-            let mon = CalorieCounter::new(stats, cals);
-            exh_recur(&mut ctx, &mon, n1, total, Stats::zero())
-        },
+    let best = match opt_cals {
+        None => exh_par(stats, total, &Whatever),
+        Some(cals) => exh_par(stats, total, &CalorieCounter::new(stats, cals)),
     };
-    ctx.best.unwrap()
+    let counts = best.counts();
+    (best.unwrap(), counts)
 }
 
 // Conjecture: this problem is amenable to hill-climbing.  ...without
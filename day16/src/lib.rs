@@ -0,0 +1,48 @@
+extern crate util;
+mod parser;
+mod sue;
+
+use sue::UrSue;
+use parser::sues;
+use util::Input;
+use util::scan::scan;
+use util::solution::{json_mode, Answer, Solution};
+
+pub fn main() {
+    json_mode(16, &Day);
+    let ur_sue = UrSue::the();
+    for line in Input::stdin().lines() {
+        let line = line.unwrap_or_else(|err| panic!("{}", err));
+        let (sue1, sue2) = scan(line.num, &line.text, sues)
+            .unwrap_or_else(|err| panic!("{}", err));
+        if sue1.test(&ur_sue) {
+            println!("Sue for Part 1: {}", sue1.ident);
+        }
+        if sue2.test(&ur_sue) {
+            println!("Sue for Part 2: {}", sue2.ident);
+        }
+    }
+}
+
+fn find_sue(input: &str, part2: bool) -> Answer {
+    let ur_sue = UrSue::the();
+    for line in Input::text(input).lines() {
+        let line = line?;
+        let (sue1, sue2) = scan(line.num, &line.text, sues)?;
+        let sue = if part2 { sue2 } else { sue1 };
+        if sue.test(&ur_sue) {
+            return Ok(sue.ident.to_owned());
+        }
+    }
+    Err("no Sue matches".into())
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        find_sue(input, false)
+    }
+    fn part2(&self, input: &str) -> Answer {
+        find_sue(input, true)
+    }
+}
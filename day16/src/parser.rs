@@ -1,37 +1,25 @@
-use regex::Regex;
 use sue::{Sue,UrSue,Count,Pred};
+use util::input::LineError;
+use util::scan::Scanner;
 
-pub struct Parser {
-    line: Regex,
-    kv: Regex,
+fn fact<'a>(s: &mut Scanner<'a>) -> Result<(&'a str, Count), LineError> {
+    let k = s.word()?;
+    s.expect(":")?;
+    Ok((k, s.int()?))
 }
-impl Parser {
-    // This one is a little more permissive than the other.  Maybe
-    // we're trying to gather all the evidence possible or something?
-    // I don't know.
-    pub fn new() -> Parser { Parser {
-        line: Regex::new(r"^Sue\s+(\w+)\s*:(.*)").unwrap(),
-        kv: Regex::new(r"\b(\w+)\s*:\s*([0-9]+)\b").unwrap(),
-    }}
-    pub fn parse_ur<'a, 'b>(&'a self, line: &'b str) -> Option<(&'b str, UrSue<'b>)> {
-        self.line.captures(line).map(|lcaps| {
-            let (ident, kvs) = (lcaps.at(1).unwrap(), lcaps.at(2).unwrap());
-            (ident, UrSue {
-                facts: self.kv.captures_iter(kvs).flat_map(|kvcaps| {
-                    let (kstr, vstr) = (kvcaps.at(1).unwrap(), kvcaps.at(2).unwrap());
-                    vstr.parse().ok().map(|v| {
-                        (kstr, v)
-                    })
-                }).collect()
-            })
-        })
-    }
-    pub fn parse<'a, 'b>(&'a self, line: &'b str) -> Option<(Sue<'b>, Sue<'b>)> {
-        self.parse_ur(line).map(|(name, ur)| {
-            (ur.reify(name, |k, v| mkpred(false, k, v)),
-             ur.reify(name, |k, v| mkpred(true, k, v)))
-        })
-    }
+
+pub fn ur_sue<'a>(s: &mut Scanner<'a>) -> Result<(&'a str, UrSue<'a>), LineError> {
+    s.expect("Sue")?;
+    let ident = s.word()?;
+    s.expect(":")?;
+    let facts = s.sep_by(",", fact)?;
+    Ok((ident, UrSue { facts: facts.into_iter().collect() }))
+}
+
+pub fn sues<'a>(s: &mut Scanner<'a>) -> Result<(Sue<'a>, Sue<'a>), LineError> {
+    let (name, ur) = ur_sue(s)?;
+    Ok((ur.reify(name, |k, v| mkpred(false, k, v)),
+        ur.reify(name, |k, v| mkpred(true, k, v))))
 }
 
 fn mkpred(p2: bool, k: &str, v: Count) -> Pred {
@@ -44,14 +32,12 @@ fn mkpred(p2: bool, k: &str, v: Count) -> Pred {
 
 #[cfg(test)]
 mod tests {
-    use super::Parser;
+    use util::scan::scan;
+    use super::ur_sue;
 
     #[test]
     fn example_as_ur() {
-        let (name, ur) = {
-            let p = Parser::new();
-            p.parse_ur("Sue 124: cars: 10, children: 1, trees: 0").unwrap()
-        };
+        let (name, ur) = scan(1, "Sue 124: cars: 10, children: 1, trees: 0", ur_sue).unwrap();
         assert_eq!(name, "124");
         assert_eq!(ur.facts.len(), 3);
         assert_eq!(ur.facts.get("cars"), Some(&10));
@@ -59,4 +45,13 @@ mod tests {
         assert_eq!(ur.facts.get("trees"), Some(&0));
         assert_eq!(ur.facts.get("124"), None);
     }
+
+    #[test]
+    fn errors() {
+        let err = |text| scan(1, text, ur_sue).map(|_| ()).unwrap_err().to_string();
+        assert_eq!(err("Sue 124: cars: 10, children: lots"),
+                   "line 1, column 30: bad number \"lots\": invalid digit found in string");
+        assert_eq!(err("Sue 124: cars: 10 children: 1"),
+                   "line 1, column 19: unexpected text \"children: 1\" at end of line");
+    }
 }
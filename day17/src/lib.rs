@@ -0,0 +1,57 @@
+extern crate util;
+mod exhaustive;
+mod witnessed;
+mod pascal;
+
+use std::io::{stdin,BufRead};
+use std::env;
+use util::Input;
+use util::subset_sum;
+use util::solution::{json_mode, Answer, Solution};
+
+pub type Vol = u16;
+pub type Num = u64;
+
+pub fn main() {
+    json_mode(17, &Day);
+    let mut args = env::args();
+    let _ = args.next();
+    let target: Vol = args.next().expect("first argument is total eggnog volume").parse().unwrap();
+    let stdin = stdin();
+    let nums: Vec<Vol> =
+        stdin.lock().lines().map(|l| l.expect("I/O error").parse().expect("NaN")).collect();
+    let cmd = args.next().unwrap_or("".to_owned());
+    if "convolution".starts_with(&cmd) {
+        println!("{}", pascal::subset_sum_at(&nums, target));
+    } else if "exhaustive".starts_with(&cmd) {
+        println!("{}", exhaustive::exhaustive(&nums, target));
+    } else if "list".starts_with(&cmd) {
+        for way in witnessed::eggnog_iter(&nums, target) {
+            println!("{:?}", way);
+        }
+    } else if "minimal".starts_with(&cmd) {
+        let ways = subset_sum::count_by_size(&nums, target);
+        match ways.iter().position(|&w| w > 0) {
+            Some(k) => println!("{} ways with {} containers", ways[k], k),
+            None => println!("No way at all"),
+        }
+    } else {
+        panic!("Unrecognized command {:?}", cmd);
+    }
+}
+
+// How much eggnog there is, per the puzzle text.
+const EGGNOG: Vol = 150;
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        let nums: Vec<Vol> = Input::text(input).parse_lines(str::parse)?;
+        Ok(pascal::subset_sum_at(&nums, EGGNOG).to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        let nums: Vec<Vol> = Input::text(input).parse_lines(str::parse)?;
+        let ways = subset_sum::count_by_size(&nums, EGGNOG);
+        Ok(ways.into_iter().find(|&w| w > 0).unwrap_or(0).to_string())
+    }
+}
@@ -1,5 +1,7 @@
 use std::cmp::min;
+#[cfg(test)]
 use std::ops::{Add,Mul};
+use util::subset_sum;
 use ::{Vol, Num};
 
 fn min_or<T: Ord>(v1o: Option<T>, v2: T) -> T {
@@ -9,6 +11,9 @@ fn min_or<T: Ord>(v1o: Option<T>, v2: T) -> T {
     }
 }
 
+// Multiplying out the product of (1 + x^n) for each n is the other way
+// to count the subsets for each sum; it's kept to check the DP against.
+#[cfg(test)]
 fn convolve<T, U, V>(us: &[T], ts: &[U], limit: Option<usize>) -> Vec<V>
     where T: Mul<U, Output=V> + Clone,
           U: Clone,
@@ -35,30 +40,14 @@ fn convolve<T, U, V>(us: &[T], ts: &[U], limit: Option<usize>) -> Vec<V>
     }).collect()
 }
 
+// The number of subsets for each sum, up to `limit` or the sum of
+// everything, whichever's less.
 pub fn subset_sums(ns: &[Vol], limit: Option<usize>) -> Vec<Num> {
-    if ns.len() == 0 {
-        vec![1]
-    } else if ns.len() == 1 {
-        let n = ns[0] as usize;
-        let mut acc = vec![0; min_or(limit, n) + 1];
-        acc[0] = 1;
-        if acc.len() > n {
-            acc[n] = 1;
-        }
-        acc
-    } else {
-        let (ls, rs) = ns.split_at(ns.len() / 2);
-        let lsss = subset_sums(ls, limit);
-        let rsss = subset_sums(rs, limit);
-        convolve(&lsss, &rsss, limit)
-    }
+    let total = ns.iter().map(|&n| n as usize).sum();
+    subset_sum::count_by_sum(ns, min_or(limit, total))
 }
 
 pub fn subset_sum_at(ns: &[Vol], target: Vol) -> Num {
-    // The last convolve could be optimized, but I don't quite feel
-    // like threading around another parameter that's useful only in
-    // the root of the recursion.  (Also the input could be
-    // pre-divided by its gcd but that's not too useful here.)
     subset_sums(ns, Some(target as usize))[target as usize]
 }
 
@@ -115,4 +104,17 @@ mod tests {
         assert_eq!(subset_sums(&[20/5, 15/5, 10/5, 5/5, 5/5], None),
                    vec![1, 2, 2, 3, 4, 4, 4, 4, 3, 2, 2, 1]);
     }
+
+    #[test]
+    fn polynomial() {
+        let ns = [7, 1, 12, 3, 3, 9, 4, 1];
+        let product = ns.iter().fold(vec![1u64], |acc, &n| {
+            let mut term = vec![0; n as usize + 1];
+            term[0] = 1;
+            term[n as usize] = 1;
+            convolve(&acc, &term, None)
+        });
+        assert_eq!(subset_sums(&ns, None), product);
+        assert_eq!(subset_sums(&ns, Some(10)), &product[..11]);
+    }
 }
@@ -2,7 +2,7 @@ use util::SubsetSumIter;
 use ::Vol;
 
 pub fn eggnog_iter(vols: &[Vol], target: Vol) -> Box<Iterator<Item=Vec<Vol>>> {
-    Box::new(SubsetSumIter::new(vols, target).map(|(used, _unused)| used))
+    Box::new(SubsetSumIter::new(vols, target).chosen())
 }
 
 #[cfg(test)]
@@ -0,0 +1,240 @@
+extern crate util;
+
+use std::env;
+use util::{Grid,Input};
+use util::grid::{Conn,Edge};
+use util::input::LineError;
+use util::solution::{json_mode, Answer, Solution};
+
+type Lights = Grid<bool>;
+
+fn stick_corners(g: &mut Lights) {
+    let (w, h) = (g.width(), g.height());
+    if w > 0 && h > 0 {
+        for &corner in [(0, 0), (w - 1, 0), (0, h - 1), (w - 1, h - 1)].iter() {
+            g[corner] = true;
+        }
+    }
+}
+
+fn life_step(g: &Lights, stuck: bool) -> Lights {
+    let mut next = Grid::from_fn(g.width(), g.height(), |x, y| {
+        let lit = g.neighbours(x, y, Conn::Eight, Edge::Clip).filter(|&nbr| g[nbr]).count();
+        lit == 3 || (lit == 2 && g[(x, y)])
+    });
+    if stuck {
+        stick_corners(&mut next);
+    }
+    next
+}
+
+// If the corners are stuck, they're stuck on from the start.
+fn run_life(mut g: Lights, n: u64, stuck: bool) -> Lights {
+    if stuck {
+        stick_corners(&mut g);
+    }
+    for _ in 0..n {
+        g = life_step(&g, stuck);
+    }
+    g
+}
+
+fn popcount(g: &Lights) -> usize {
+    g.iter().filter(|&&b| b).count()
+}
+
+pub fn main() {
+    json_mode(18, &Day);
+    let n: u64 = env::args()
+        .nth(1).expect("give number of iterations as argument")
+        .parse().unwrap();
+    let is_print = "print".starts_with(&env::args().nth(2).unwrap_or("count".to_owned()));
+
+    let ur_input = Input::stdin().read_text().map_err(|e| e.to_string())
+        .and_then(|text| Grid::parse(&text).map_err(|e| e.to_string()))
+        .unwrap_or_else(|msg| panic!("{}", msg));
+    for &stuck in [false, true].iter() {
+        let label = if stuck { "Stuck" } else { "Unstuck" };
+        let output = run_life(ur_input.clone(), n, stuck);
+        if is_print {
+            println!("{}:", label);
+            print!("{}", output.render());
+        } else {
+            println!("{}: {}", label, popcount(&output));
+        }
+    }
+}
+
+// How many steps to animate, per the puzzle text.
+const STEPS: u64 = 100;
+
+fn lit_after(input: &str, stuck: bool) -> Result<usize, LineError> {
+    Ok(popcount(&run_life(Grid::parse(input)?, STEPS, stuck)))
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        Ok(lit_after(input, false)?.to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        Ok(lit_after(input, true)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::Grid;
+    use super::run_life;
+
+    fn run(strs: &[&str], n: u64, stuck: bool) -> Vec<String> {
+        let grid = Grid::parse(&strs.join("\n")).unwrap();
+        run_life(grid, n, stuck).render().lines().map(|l| l.to_owned()).collect()
+    }
+
+    fn own(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|&s| s.to_owned()).collect()
+    }
+
+    #[test]
+    fn blink() {
+        assert_eq!(run(&["...",
+                         "###",
+                         "..."], 1, false),
+                   own(&[".#.",
+                         ".#.",
+                         ".#."]));
+        assert_eq!(run(&[".#.",
+                         ".#.",
+                         ".#."], 1, false),
+                   own(&["...",
+                         "###",
+                         "..."]));
+    }
+    
+    #[test]
+    fn blink_more() {
+        assert_eq!(run(&["...",
+                         "###",
+                         "..."], 99, false),
+                   own(&[".#.",
+                         ".#.",
+                         ".#."]));
+        assert_eq!(run(&[".#.",
+                         ".#.",
+                         ".#."], 99, false),
+                   own(&["...",
+                         "###",
+                         "..."]));
+    }
+
+    #[test]
+    fn example() {
+        const STUFF: [&'static[&'static str]; 5] = [
+            &[".#.#.#",
+              "...##.",
+              "#....#",
+              "..#...",
+              "#.#..#",
+              "####.."],
+
+            &["..##..",
+              "..##.#",
+              "...##.",
+              "......",
+              "#.....",
+              "#.##.."],
+
+            &["..###.",
+              "......",
+              "..###.",
+              "......",
+              ".#....",
+              ".#...."],
+
+            &["...#..",
+              "......",
+              "...#..",
+              "..##..",
+              "......",
+              "......"],
+
+            &["......",
+              "......",
+              "..##..",
+              "..##..",
+              "......",
+              "......"]];
+
+        for i in 0..STUFF.len() {
+            for j in i..STUFF.len() {
+                assert_eq!(run(STUFF[i], (j - i) as u64, false), own(STUFF[j]))
+            }
+        }
+    }
+
+    #[test]
+    fn example_stuck() {
+        const STUFF: [&'static[&'static str]; 6] = [
+            &["##.#.#",
+              "...##.",
+              "#....#",
+              "..#...",
+              "#.#..#",
+              "####.#"],
+
+            &["#.##.#",
+              "####.#",
+              "...##.",
+              "......",
+              "#...#.",
+              "#.####"],
+
+            &["#..#.#",
+              "#....#",
+              ".#.##.",
+              "...##.",
+              ".#..##",
+              "##.###"],
+
+            &["#...##",
+              "####.#",
+              "..##.#",
+              "......",
+              "##....",
+              "####.#"],
+
+            &["#.####",
+              "#....#",
+              "...#..",
+              ".##...",
+              "#.....",
+              "#.#..#"],
+
+            &["##.###",
+              ".##..#",
+              ".##...",
+              ".##...",
+              "#.#...",
+              "##...#"]];
+
+        for i in 0..STUFF.len() {
+            for j in i..STUFF.len() {
+                assert_eq!(run(STUFF[i], (j - i) as u64, true), own(STUFF[j]))
+            }
+        }
+    }
+    
+    #[test]
+    fn glide() {
+        assert_eq!(run(&[".#..",
+                         "..#.",
+                         "###.",
+                         "...."], 4, false),
+
+                   own(&["....",
+                         "..#.",
+                         "...#",
+                         ".###"]));
+    }
+}
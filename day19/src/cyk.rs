@@ -1,5 +1,7 @@
 use std::cmp::min;
+use std::marker::PhantomData;
 use std::ops::Add;
+use util::symtab::SymId;
 
 // https://en.wikipedia.org/wiki/CYK_algorithm
 
@@ -23,30 +25,36 @@ impl Cost {
     }
 }
 
-pub struct CYK {
+// The terminals and nonterminals are whatever kind of id `I` is, from
+// 0 up to `syms`.
+pub struct CYK<I> {
     syms: usize,
     next: Sym,
     prods: Vec<(Sym, [Sym; 2], Cost)>,
+    ids: PhantomData<I>,
 }
-impl CYK {
+impl<I: SymId> CYK<I> {
     pub fn new(syms: usize) -> Self { CYK {
         syms: syms,
         next: Sym(syms),
         prods: Vec::new(),
+        ids: PhantomData,
     }}
     fn alloc_nt(&mut self) -> Sym {
         let rv = self.next;
         self.next.0 += 1;
         rv
     }
-    pub fn add_rule(&mut self, lhs: usize, rhs: &[usize]) {
+    pub fn add_rule(&mut self, lhs: I, rhs: &[I]) {
+        let lhs = lhs.index();
+        let rhs: Vec<usize> = rhs.iter().map(|&rh| rh.index()).collect();
         assert!(lhs < self.syms, "CYK::add_rule: lhs out of bounds");
         for (i, &rh) in rhs.iter().enumerate() {
             assert!(rh < self.syms, "CYK::add_rule: rhs[{}] out of bounds", i);
         }
         assert!(rhs.len() >= 2, "CYK::add_rule: rhs.len() < 2 not implemented");
         let mut lhs = Sym(lhs);
-        let mut rhs = rhs;
+        let mut rhs = &rhs[..];
         while rhs.len() > 2 {
             let inter = self.alloc_nt();
             self.prods.push((lhs, [Sym(rhs[0]), inter], Cost(0)));
@@ -55,14 +63,14 @@ impl CYK {
         }
         self.prods.push((lhs, [Sym(rhs[0]), Sym(rhs[1])], Cost(1)));
     }
-    pub fn solve(&self, starts: &[usize], sentence: &[usize]) -> Option<usize> {
+    pub fn solve(&self, starts: &[I], sentence: &[I]) -> Option<usize> {
         for (i, &start) in starts.iter().enumerate() {
-            assert!(start < self.syms, "CYK::solve: starts[{}] out of bounds", i);
+            assert!(start.index() < self.syms, "CYK::solve: starts[{}] out of bounds", i);
         }
         let mut state = Table::new(sentence.len(), self.next);
         for (i, &ltr) in sentence.iter().enumerate() {
-            assert!(ltr < self.syms, "CYK::solve: sentence[{}] out of bounds", i);
-            state.write(i, 1, Sym(ltr), Cost(0));
+            assert!(ltr.index() < self.syms, "CYK::solve: sentence[{}] out of bounds", i);
+            state.write(i, 1, Sym(ltr.index()), Cost(0));
         }
         // Hey, maybe *not* naming the variables i, j, k will be clearer.
         for length in 2..(sentence.len() + 1) {
@@ -77,7 +85,7 @@ impl CYK {
                 }
             }
         }
-        starts.iter().map(|&start| state.read(0, sentence.len(), Sym(start)))
+        starts.iter().map(|&start| state.read(0, sentence.len(), Sym(start.index())))
             .fold(Cost::nope(), |ca, cb| min(ca, cb)).externalize()
     }
 }
@@ -118,7 +126,7 @@ mod tests {
 
     #[test]
     fn hohoho() {
-        let mut cyk = CYK::new(2);
+        let mut cyk = CYK::<usize>::new(2);
         cyk.add_rule(1, &[1, 0]);
         cyk.add_rule(1, &[0, 1]);
         cyk.add_rule(0, &[1, 1]);
@@ -0,0 +1,303 @@
+extern crate util;
+mod cyk;
+
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use util::{search,Input};
+use util::symtab::Id;
+use util::input::{Line,LineError};
+use util::scan::{scan,Scanner};
+use util::solution::{json_mode, Answer, Solution};
+use cyk::CYK;
+
+// The atoms in a molecule get their own kind of id.
+enum Atom {}
+type SymTab = ::util::SymTab<Id<Atom>>;
+
+struct Problem {
+    rewrites: Vec<(String, String)>,
+}
+impl Problem {
+    #![allow(dead_code)]
+    fn new() -> Self {
+        Problem { rewrites: vec![] }
+    }
+    fn add(&mut self, lhs: &str, rhs: &str) {
+        self.rewrites.push((lhs.to_owned(), rhs.to_owned()));
+    }
+    fn add_rules(&mut self, lines: &[Line]) -> Result<(), LineError> {
+        for line in lines {
+            let (lhs, rhs) = scan(line.num, &line.text, rule)?;
+            self.add(lhs, rhs);
+        }
+        Ok(())
+    }
+    fn from_rules(lines: &[Line]) -> Result<Self, LineError> {
+        let mut that = Self::new();
+        that.add_rules(lines)?;
+        Ok(that)
+    }
+    fn rewrite(&self, before: &str) -> HashSet<String> {
+        let mut set = HashSet::new();
+        self.rewrite_into(before, &mut set);
+        set
+    }
+    fn rewrite_into(&self, before: &str, set: &mut HashSet<String>) {
+        for rw in self.rewrites.iter() {
+            let mut cursor = 0;
+            while let Some(offset) = before[cursor..].find(&rw.0) {
+                let start = cursor + offset;
+                let end = start + rw.0.len();
+                cursor = start + 1;
+
+                let mut after = String::new();
+                after.push_str(&before[..start]);
+                after.push_str(&rw.1);
+                after.push_str(&before[end..]);
+                set.insert(after);
+            }
+        }
+    }
+    // Iterative deepening, rather than breadth first, so that what it
+    // remembers about molecules already seen is bounded.
+    fn search(&self, before: &str, after: &str) -> usize {
+        let found = search::iddfs(&Derivation { rules: self, before, after }, 1, 1);
+        found.unwrap_or_else(|| panic!("{:?} can't be reached from {:?}", after, before)).cost
+    }
+    fn invert(&self) -> Self {
+        Problem { rewrites: self.rewrites.iter().cloned().map(|(l,r)| (r,l)).collect() }
+    }
+    fn search_fast(&self, before: &str, after: &str) -> Option<usize> {
+        let mut stab = SymTab::default();
+        let mut starts = Vec::new();
+        let mut parsed_rew = Vec::new();
+        let target;
+        {
+            let mut parse = |s: &str| {
+                atoms(s).into_iter().map(|a| stab.read(a)).collect::<Vec<_>>()
+            };
+            for &(ref lhs, ref rhs) in &self.rewrites {
+                let prhs = parse(rhs);
+                match prhs.len() {
+                    0 => panic!("epsilon production for {:?} forbidden", lhs),
+                    1 => {
+                        assert!(lhs == before, "non-start unit production unimplemented");
+                        starts.push(prhs[0]);
+                    },
+                    _ => {
+                        let plhs = parse(lhs);
+                        assert!(plhs.len() == 1, "context-free grammars only, please");
+                        parsed_rew.push((plhs[0], prhs));
+                    }
+                }
+            }
+            target = parse(after);
+        }
+        let adjust = if starts.is_empty() {
+            starts.push(stab.read(before));
+            0
+        } else {
+            1
+        };
+        let stab = stab.freeze();
+        let mut cyk = CYK::new(stab.len());
+        for (plhs, prhs) in parsed_rew {
+            cyk.add_rule(plhs, &prhs);
+        }
+        cyk.solve(&starts, &target).map(|u| u + adjust)
+    }
+}
+
+// Getting from one molecule to another by rewriting, one step at a time.
+struct Derivation<'a> {
+    rules: &'a Problem,
+    before: &'a str,
+    after: &'a str,
+}
+
+impl<'a> search::Problem for Derivation<'a> {
+    type State = String;
+    type Step = String;
+    type Cost = usize;
+
+    fn start(&self) -> String {
+        self.before.to_owned()
+    }
+    // The step is the molecule it leads to, so the path is every molecule
+    // along the way; they're sorted so that it's always the same path.
+    fn successors(&self, st: &String) -> Vec<(String, String, usize)> {
+        let mut next: Vec<_> = self.rules.rewrite(st).into_iter().collect();
+        next.sort();
+        next.into_iter().map(|s| (s.clone(), s, 1)).collect()
+    }
+    fn is_goal(&self, st: &String) -> bool {
+        st == self.after
+    }
+}
+
+// An element is a capital letter and then any lowercase ones; the
+// left-hand side can also be all lowercase, like the starting "e".
+fn molecule<'a>(s: &mut Scanner<'a>, or_lower: bool) -> Result<&'a str, LineError> {
+    let m = s.word()?;
+    let ok = if m.starts_with(|c: char| c.is_ascii_uppercase()) {
+        m.chars().all(|c| c.is_ascii_alphabetic())
+    } else {
+        or_lower && m.chars().all(|c| c.is_ascii_lowercase())
+    };
+    if ok { Ok(m) } else { Err(s.error_at(m, format!("{:?} isn't a molecule", m))) }
+}
+
+fn rule<'a>(s: &mut Scanner<'a>) -> Result<(&'a str, &'a str), LineError> {
+    let lhs = molecule(s, true)?;
+    s.expect("=>")?;
+    Ok((lhs, molecule(s, false)?))
+}
+
+// Splits a molecule into its elements (or the single all-lowercase
+// start symbol); anything else would have been rejected by `molecule`.
+fn atoms(s: &str) -> Vec<&str> {
+    let mut acc = Vec::new();
+    let mut begin = 0;
+    for (i, c) in s.char_indices().skip(1) {
+        assert!(c.is_ascii_alphabetic(), "{:?} isn't a molecule", s);
+        if c.is_ascii_uppercase() {
+            acc.push(&s[begin..i]);
+            begin = i;
+        }
+    }
+    if !s.is_empty() {
+        acc.push(&s[begin..]);
+    }
+    acc
+}
+
+fn read_problem(input: Input) -> Result<(Problem, String), Box<dyn Error>> {
+    let mut sections = input.sections()?.into_iter();
+    let rules = sections.next().ok_or("no rewrite rules")?;
+    let prob = Problem::from_rules(&rules)?;
+    match (sections.next(), sections.next()) {
+        (Some(ref target), None) if target.len() == 1 => Ok((prob, target[0].text.clone())),
+        _ => Err("expected one target string after blank line".into()),
+    }
+}
+
+pub fn main() {
+    json_mode(19, &Day);
+    let input = Input::from_arg(env::args().nth(1)).expect("can't open input");
+    let (prob, input) = read_problem(input).unwrap_or_else(|err| panic!("{}", err));
+    println!("Calibration: {}", prob.rewrite(&input).len());
+    println!("Path length (fast): {:?}", prob.search_fast("e", &input));
+    // println!("Path length (fwd): {}", prob.search("e", &input));
+    // println!("Path length (inv): {}", prob.search(&input, "e"));
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        let (prob, target) = read_problem(Input::text(input))?;
+        Ok(prob.rewrite(&target).len().to_string())
+    }
+    fn part2(&self, input: &str) -> Answer {
+        let (prob, target) = read_problem(Input::text(input))?;
+        let steps = prob.search_fast("e", &target).ok_or("can't make that molecule")?;
+        Ok(steps.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::Input;
+    use util::input::Line;
+    use super::{atoms, Problem};
+
+    fn lines(text: &str) -> Vec<Line> {
+        Input::text(text).read_lines().unwrap()
+    }
+
+    fn get_example() -> Problem {
+        Problem::from_rules(&lines("H => HO\nH => OH\nO => HH")).unwrap()
+    }
+
+    fn get_example2() -> Problem {
+        let mut p = get_example();
+        p.add_rules(&lines("e => H\ne => O")).unwrap();
+        p
+    }
+
+    #[test]
+    fn bad_rules() {
+        let err = |text| Problem::from_rules(&lines(text)).err().unwrap().to_string();
+        assert_eq!(err("H => HO\nH -> OH"),
+                   "line 2, column 3: expected \"=>\", found \"->\"");
+        assert_eq!(err("e => h"), "line 1, column 6: \"h\" isn't a molecule");
+        assert_eq!(err("Ca2 => CaCa"), "line 1, column 1: \"Ca2\" isn't a molecule");
+    }
+
+    #[test]
+    fn split_atoms() {
+        assert_eq!(atoms("CRnCaSiRnBSi"), vec!["C", "Rn", "Ca", "Si", "Rn", "B", "Si"]);
+        assert_eq!(atoms("e"), vec!["e"]);
+        assert_eq!(atoms(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn example() {
+        let stuff = get_example().rewrite("HOH");
+        assert_eq!(stuff.len(), 4);
+        assert!(stuff.contains("HOOH"));
+        assert!(stuff.contains("HOHO"));
+        assert!(stuff.contains("OHOH"));
+        assert!(stuff.contains("HHHH"));
+        assert_eq!(get_example().rewrite("HOHOHO").len(), 7);
+    }
+
+    #[test]
+    fn example_weirdchar() {
+        let mut p = Problem::new();
+        p.add("H", "OO");
+        let stuff = p.rewrite("H2O");
+        assert_eq!(stuff.len(), 1);
+        assert!(stuff.contains("OO2O"));
+    }
+
+    #[test]
+    fn example_path() {
+        let p = get_example2();
+        assert_eq!(p.search("e", "HOH"), 3);
+        assert_eq!(p.search("e", "HOHOHO"), 6);
+    }
+
+    #[test]
+    fn example_inv() {
+        let p = get_example().invert();
+        for s in &["HOOH", "HOHO", "OHOH", "HHHH"] {
+            assert!(p.rewrite(s).contains("HOH"));
+        }
+    }
+
+    #[test]
+    fn path_inv() {
+        let p = get_example2().invert();
+        assert_eq!(p.search("HOH", "e"), 3);
+        assert_eq!(p.search("HOHOHO", "e"), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "\"X\" can't be reached")]
+    fn path_none() {
+        let mut p = Problem::new();
+        p.add("e", "H");
+        p.add("H", "O");
+        p.add("O", "H");
+        p.search("e", "X");
+    }
+
+    #[test]
+    fn path_fast() {
+        let p = get_example2();
+        assert_eq!(p.search_fast("e", "HOH"), Some(3));
+        assert_eq!(p.search_fast("e", "HOHOHO"), Some(6));
+        assert_eq!(p.search_fast("e", "OOO"), None);
+    }
+}
@@ -0,0 +1,166 @@
+extern crate util;
+mod rules;
+
+use std::env;
+use std::error::Error;
+use util::{search,Input,Json};
+use util::best::Counts;
+use util::solution::{json_mode, Answer, Report, Solution};
+use rules::{World,State,Spell,Won,Lost,Ok};
+
+fn parse_args<I>(mut i: I) -> (World, State)
+    where I: Iterator<Item=String> {
+    let mut player_hp = 50u16;
+    let mut player_mana = 500u16;
+    let mut boss_hp = None::<u16>;
+    let mut boss_dmg = None::<u16>;
+    let mut hardness = 0u16;
+    while let Some(word) = i.next() {
+        let value = i.next().expect("expected key/value pairs as arguments but got odd number");
+        let word = word.to_lowercase();
+        let is_player = word.contains("player");
+        let is_hp = word.contains("hp") || (word.contains("hit") && word.contains("point"));
+        let is_mana = word.contains("mana");
+        let is_boss = word.contains("boss");
+        let is_dmg = word.contains("damage") || word.contains("dmg");
+        let is_hard = word.contains("hard");
+        if is_player && is_hp {
+            player_hp = value.parse().unwrap();
+        } else if is_mana {
+            player_mana = value.parse().unwrap();
+        } else if is_boss && is_hp {
+            boss_hp = Some(value.parse().unwrap())
+        } else if is_boss && is_dmg {
+            boss_dmg = Some(value.parse().unwrap())
+        } else if is_hard {
+            hardness = value.parse().unwrap();
+        } else {
+            panic!("unrecognized key word {}", word);
+        }
+    }
+    let boss_hp = boss_hp.expect("boss HP not specified");
+    let boss_dmg = boss_dmg.expect("boss damage not specified");
+    (World::new_hard(boss_dmg, hardness), State::new(player_hp, player_mana, boss_hp))
+}
+
+// A fight as a search problem: each step is a spell, and costs its mana.
+struct Fight<'a> {
+    w: &'a World,
+    st: &'a State,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Node {
+    Fighting(State),
+    Won,
+}
+
+impl<'a> search::Problem for Fight<'a> {
+    type State = Node;
+    type Step = Spell;
+    // ...why did I not typedef the numbers in rules.rs?  Oh well.
+    type Cost = u32;
+
+    fn start(&self) -> Node {
+        Node::Fighting(self.st.clone())
+    }
+    fn successors(&self, node: &Node) -> Vec<(Spell, Node, u32)> {
+        let st = match *node {
+            Node::Fighting(ref st) => st,
+            Node::Won => return vec![],
+        };
+        Spell::all().filter_map(|sp| match st.clone().round(self.w, sp) {
+            Lost => None,
+            Won(win) => Some((sp, Node::Won, win.spent - st.spent())),
+            Ok(nst) => {
+                let cost = nst.spent() - st.spent();
+                Some((sp, Node::Fighting(nst), cost))
+            }
+        }).collect()
+    }
+    fn is_goal(&self, node: &Node) -> bool {
+        *node == Node::Won
+    }
+}
+
+// Different orders of spells often lead to the same state, and since the
+// state includes the mana spent so far everything after it is the same
+// too; `iddfs` remembers them and doesn't search them again.
+fn full_search(w: &World, st: &State) -> (u32, Vec<Spell>, Counts) {
+    let (found, counts) = search::iddfs_counted(&Fight { w, st }, 5, 3);
+    let found = found.expect("there's no way to win");
+    (found.cost, found.path, counts)
+}
+
+pub fn main() {
+    json_mode(22, &Day);
+    let (world, state) = parse_args(env::args().skip(1));
+    let (cost, spells, counts) = full_search(&world, &state);
+    println!("Minimal cost: {}", cost);
+    println!("Spell sequence: {:?}", spells);
+    eprintln!("(search: {})", counts);
+}
+
+// The witness is the spells cast, in order.
+fn min_cost(input: &str, hardness: u16) -> Result<Report, Box<dyn Error>> {
+    let mut args = Input::text(input).key_value_args("boss")?;
+    args.push("hardness".to_owned());
+    args.push(hardness.to_string());
+    let (world, state) = parse_args(args.into_iter());
+    let (cost, spells, _) = full_search(&world, &state);
+    Result::Ok(Report::with_witness(cost.to_string(), Json::obj(vec![
+        ("spells", Json::arr(spells.iter().map(|sp| format!("{:?}", sp)))),
+        ("mana", Json::from(cost)),
+    ])))
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        min_cost(input, 0).map(|r| r.answer)
+    }
+    fn part2(&self, input: &str) -> Answer {
+        min_cost(input, 1).map(|r| r.answer)
+    }
+    fn report(&self, n: u32, input: &str) -> Result<Report, Box<dyn Error>> {
+        match n {
+            1 => min_cost(input, 0),
+            2 => min_cost(input, 1),
+            _ => self.part(n, input).map(Report::new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::solution::Solution;
+    use super::{parse_args, full_search, Day};
+    use rules::{MagicMissile, Drain, Shield, Poison, Recharge};
+
+    // How convenient that the examples happen to be minimal.
+
+    #[test]
+    fn example1() {
+        let args = "playerhp 10 mana 250 bosshp 13 bossdmg 8".split(' ').map(|s| s.to_owned());
+        let (world, state) = parse_args(args);
+        let (_, ritual, _) = full_search(&world, &state);
+        assert_eq!(ritual, vec![Poison, MagicMissile]);
+    }
+
+    #[test]
+    fn example2() {
+        let args = "playerhp 10 mana 250 bosshp 14 bossdmg 8".split(' ').map(|s| s.to_owned());
+        let (world, state) = parse_args(args);
+        let (_, ritual, _) = full_search(&world, &state);
+        assert_eq!(ritual, vec![Recharge, Shield, Drain, Poison, MagicMissile]);
+    }
+
+    #[test]
+    fn report() {
+        let report = Day.report(1, "Hit Points: 13\nDamage: 8\n").unwrap();
+        assert_eq!(report.answer, "212");
+        assert_eq!(report.witness.unwrap().to_string(),
+                   "{\"spells\":[\"MagicMissile\",\"MagicMissile\",\"MagicMissile\",\
+                    \"MagicMissile\"],\"mana\":212}");
+    }
+}
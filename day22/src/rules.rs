@@ -133,7 +133,7 @@ impl World {
 // This is small enough to reasonably be `Copy`, but it's too easy to
 // accidentally use the wrong state if it's not affine by default.
 // Also this could be bit-packed into less space but probably not worth it.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct State {
     spent: u32,
     mana: u16,
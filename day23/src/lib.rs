@@ -0,0 +1,37 @@
+extern crate util;
+mod parser;
+mod interp;
+
+use std::convert::Into;
+use std::env;
+use parser::insn;
+use util::Input;
+use util::solution::{json_mode, Answer, Solution};
+use interp::{Nat,Reg};
+
+pub fn main() {
+    json_mode(23, &Day);
+    let input = Input::from_arg(env::args().nth(1)).expect("can't open input");
+    let prog = input.scan_lines(insn).unwrap_or_else(|err| panic!("{}", err));
+    let output0 = interp::run(&prog, [].into());
+    println!("[0] A = {}", output0[Reg::A]);
+    println!("[0] B = {}", output0[Reg::B]);
+    let output1 = interp::run(&prog, [(Reg::A, 1)].into());
+    println!("[1] A = {}", output1[Reg::A]);
+    println!("[1] B = {}", output1[Reg::B]);
+}
+
+fn reg_b(input: &str, a: Nat) -> Answer {
+    let prog = Input::text(input).scan_lines(insn)?;
+    Ok(interp::run(&prog, [(Reg::A, a)].into())[Reg::B].to_string())
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        reg_b(input, 0)
+    }
+    fn part2(&self, input: &str) -> Answer {
+        reg_b(input, 1)
+    }
+}
@@ -1,77 +1,85 @@
-use std::borrow::Borrow;
-use regex::Regex;
 use interp::{Reg,Offset,Insn};
+use util::input::LineError;
+use util::scan::Scanner;
 
-pub struct Parser {
-    opcode: Regex,
-    sep: Regex,
-}
-impl Parser {
-    pub fn new() -> Parser { Parser {
-        opcode: Regex::new(r"^\s*(\pL+)\s+(.*)").unwrap(),
-        sep: Regex::new(r"\s*,\s*|\s+").unwrap(), // "DWIM"
-    }}
-    fn parse_reg(&self, tok: &str) -> Reg {
-        match tok {
-            "a" | "A" => Reg::A,
-            "b" | "B" => Reg::B,
-            _ => panic!("invalid register {}", tok)
-        }
-    }
-    fn parse_off(&self, tok: &str) -> Offset {
-        let tok = if tok.chars().next() == Some('+') { &tok[1..] } else { tok };
-        tok.parse().unwrap_or_else(|err| panic!("invalid offset {}: {:?}", tok, err))
+fn reg(s: &mut Scanner) -> Result<Reg, LineError> {
+    match s.word()? {
+        "a" | "A" => Ok(Reg::A),
+        "b" | "B" => Ok(Reg::B),
+        huh => Err(s.error_at(huh, format!("invalid register {:?}", huh))),
     }
-    pub fn parse_line(&self, line: &str) -> Insn {
-        let o_caps = self.opcode.captures(line).expect("syntax error finding opcode");
-        let opc = &o_caps[1];        
-        let args: Vec<_> = self.sep.split(o_caps[2].trim()).collect();
-        let check = |n| if args.len() != n {
-            panic!("too {} arguments for opcode {}: got {}, expected {}",
-                   if args.len() > n { "many" } else { "few" }, opc, args.len(), n);
-        };
-        match opc.to_lowercase().borrow() {
-            "hlf" => { check(1); Insn::Hlf(self.parse_reg(args[0])) },
-            "tpl" => { check(1); Insn::Tpl(self.parse_reg(args[0])) },
-            "inc" => { check(1); Insn::Inc(self.parse_reg(args[0])) },
-            "jmp" => { check(1); Insn::Jmp(self.parse_off(args[0])) },
-            "jie" => { check(2); Insn::Jie(self.parse_reg(args[0]), self.parse_off(args[1])) },
-            "jio" => { check(2); Insn::Jio(self.parse_reg(args[0]), self.parse_off(args[1])) },
-            _ => panic!("unrecognized opcode {}", opc)
-        }
+}
+
+fn off(s: &mut Scanner) -> Result<Offset, LineError> {
+    s.int().map_err(|err| LineError { message: format!("invalid offset: {}", err.message), ..err })
+}
+
+// Arguments can be separated by a comma or just whitespace ("DWIM").
+fn reg_off(s: &mut Scanner) -> Result<(Reg, Offset), LineError> {
+    let r = reg(s)?;
+    s.opt(",");
+    Ok((r, off(s)?))
+}
+
+pub fn insn(s: &mut Scanner) -> Result<Insn, LineError> {
+    let opc = s.word()?;
+    match &opc.to_lowercase() as &str {
+        "hlf" => Ok(Insn::Hlf(reg(s)?)),
+        "tpl" => Ok(Insn::Tpl(reg(s)?)),
+        "inc" => Ok(Insn::Inc(reg(s)?)),
+        "jmp" => Ok(Insn::Jmp(off(s)?)),
+        "jie" => { let (r, o) = reg_off(s)?; Ok(Insn::Jie(r, o)) },
+        "jio" => { let (r, o) = reg_off(s)?; Ok(Insn::Jio(r, o)) },
+        _ => Err(s.error_at(opc, format!("unrecognized opcode {:?}", opc)))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Parser;
-    use interp::{Insn,Reg};
+    use util::scan::scan;
+    use super::{insn, off};
+    use interp::{Insn,Offset,Reg};
+
+    fn parse_off(text: &str) -> Offset {
+        scan(1, text, off).unwrap()
+    }
+    fn parse_line(text: &str) -> Insn {
+        scan(1, text, insn).unwrap()
+    }
 
     #[test]
     fn offsets() {
-        let p = Parser::new();
-        assert_eq!(p.parse_off("23"), 23);
-        assert_eq!(p.parse_off("+23"), 23);
-        assert_eq!(p.parse_off("-23"), -23);
+        assert_eq!(parse_off("23"), 23);
+        assert_eq!(parse_off("+23"), 23);
+        assert_eq!(parse_off("-23"), -23);
     }
 
     #[test] #[should_panic(expected = "invalid offset")]
     fn badnum1() {
-        let p = Parser::new();
-        let _o = p.parse_off("");
+        let _o = parse_off("");
     }
 
     #[test] #[should_panic(expected = "invalid offset")]
     fn badnum2() {
-        let p = Parser::new();
-        let _o = p.parse_off("b");
+        let _o = parse_off("b");
     }
     
     #[test]
     fn example() {
-        let p = Parser::new();
-        assert_eq!(p.parse_line("inc a"), Insn::Inc(Reg::A));
-        assert_eq!(p.parse_line("jio a, +2"), Insn::Jio(Reg::A, 2));
-        assert_eq!(p.parse_line("jmp +0"), Insn::Jmp(0));
+        assert_eq!(parse_line("inc a"), Insn::Inc(Reg::A));
+        assert_eq!(parse_line("jio a, +2"), Insn::Jio(Reg::A, 2));
+        assert_eq!(parse_line("JIE B -3"), Insn::Jie(Reg::B, -3));
+        assert_eq!(parse_line("jmp +0"), Insn::Jmp(0));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |text| scan(1, text, insn).unwrap_err().to_string();
+        assert_eq!(err("inc c"), "line 1, column 5: invalid register \"c\"");
+        assert_eq!(err("nop a"), "line 1, column 1: unrecognized opcode \"nop\"");
+        assert_eq!(err("jmp a, +1"),
+                   "line 1, column 5: invalid offset: bad number \"a\": \
+                    invalid digit found in string");
+        assert_eq!(err("hlf a, b"), "line 1, column 6: unexpected text \", b\" at end of line");
     }
 }
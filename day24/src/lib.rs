@@ -0,0 +1,224 @@
+extern crate util;
+use std::cmp::Ord;
+use std::env;
+use std::error::Error;
+use std::io::{stdin,BufRead};
+use util::{Input,Json,SubsetSumIter};
+use util::solution::{json_mode, Answer, Report, Solution};
+use util::subset_sum::count_by_size;
+
+type Weight = u16;
+type Entangle = u64;
+type Bag = Vec<Weight>;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Plan {
+    front: Bag,
+    back: Vec<Bag>,
+    ent: Entangle,
+}
+
+impl Plan {
+    fn new(front: Bag, back: Vec<Bag>) -> Self {
+        let ent = front.iter().fold(1 as Entangle, |e, &w| e.checked_mul(w as Entangle)
+                                      .expect("quantum entanglement overflow"));
+        Plan {
+            front: front,
+            back: back,
+            ent: ent,
+        }
+    }
+
+    fn badness(&self) -> (usize, Entangle) {
+        (self.front.len(), self.ent)
+    }
+    fn is_better(&self, other: &Self) -> bool {
+        self.badness() < other.badness()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Fail {
+    NonDiv,
+    Unsat
+}
+
+// All that matters for the non-front groups is that there exist *some* equal split.
+fn whatever(stuff: Bag, groups: usize, each: Weight) -> Option<Vec<Bag>> {
+    match groups {
+        0 => return Some(vec![]),
+        1 => return Some(vec![stuff]),
+        _ => ()
+    };
+    let mut split = SubsetSumIter::new(&stuff, each);
+    split.next().and_then(|(mine, theirs)| {
+        whatever(theirs, groups - 1, each).map(|mut rest| {
+            rest.push(mine);
+            rest
+        })
+    })
+}
+
+fn compute(mut stuff: Bag, groups: usize) -> Result<Plan, Fail> {
+    let total = stuff.iter().fold(0 as Weight, |a, &i| a.checked_add(i).expect("weight overflow"));
+    if total % groups as Weight != 0 { return Err(Fail::NonDiv) }
+    let each = total / groups as Weight;
+
+    stuff.sort_by(|a, b| b.cmp(a)); // Bias towards the larger elements in front.
+    // Try front sizes smallest first, skipping the ones that can't
+    // even make the weight; the first size with a balanced plan wins.
+    for (size, ways) in count_by_size(&stuff, each).into_iter().enumerate() {
+        if ways == 0 {
+            continue;
+        }
+        let thirds = SubsetSumIter::new(&stuff, each).of_size(size);
+        let balanced = thirds.filter_map(|(front, back)| {
+            whatever(back, groups - 1, each).map(|back| Plan::new(front, back))
+        });
+        let best = balanced.fold(None, |acc: Option<Plan>, plan| {
+            match acc {
+                Some(ref prev) if !plan.is_better(prev) => acc,
+                _ => Some(plan),
+            }
+        });
+        if let Some(plan) = best {
+            return Ok(plan);
+        }
+    }
+    Err(Fail::Unsat)
+}
+
+pub fn main() {
+    json_mode(24, &Day);
+    let n: usize = env::args().nth(1).unwrap_or_else(|| "3".to_owned()).parse().expect("NaN");
+    assert!(n > 0);
+
+    let stdin = stdin();
+    let stuff: Bag =
+        stdin.lock().lines().map(|l| l.expect("I/O error").parse().expect("NaN")).collect();
+
+    match compute(stuff, n) {
+        Err(Fail::NonDiv) => println!("Total weight not divisible by {}!", n),
+        Err(Fail::Unsat) => println!("Packages can't be divided evenly!"),
+        Ok(plan) => {
+            println!("Front: {:?}", plan.front);
+            for (i, name) in [" Left", "Right", "Trunk"].iter().enumerate() {
+                if let Some(thing) = plan.back.get(i) {
+                    println!("{}: {:?}", name, thing);
+                }
+            }
+            for (i, thing) in plan.back.iter().skip(3).enumerate() {
+                println!("Other Place #{}: {:?}", i+1, thing);
+            }
+            println!("-- ");
+            println!("Quantum Entanglement: {}", plan.ent);
+        }
+    }
+}
+
+// The answer is the front group's entanglement; the whole plan is the
+// witness.
+fn packing(input: &str, groups: usize) -> Result<Report, Box<dyn Error>> {
+    let stuff: Bag = Input::text(input).parse_lines(str::parse)?;
+    match compute(stuff, groups) {
+        Err(Fail::NonDiv) => Err(format!("total weight not divisible by {}", groups).into()),
+        Err(Fail::Unsat) => Err("packages can't be divided evenly".into()),
+        Ok(plan) => Ok(Report::with_witness(plan.ent.to_string(), Json::obj(vec![
+            ("front", Json::from(plan.front)),
+            ("back", Json::from(plan.back)),
+            ("entanglement", Json::from(plan.ent)),
+        ]))),
+    }
+}
+
+pub struct Day;
+impl Solution for Day {
+    fn part1(&self, input: &str) -> Answer {
+        packing(input, 3).map(|r| r.answer)
+    }
+    fn part2(&self, input: &str) -> Answer {
+        packing(input, 4).map(|r| r.answer)
+    }
+    fn report(&self, n: u32, input: &str) -> Result<Report, Box<dyn Error>> {
+        match n {
+            1 => packing(input, 3),
+            2 => packing(input, 4),
+            _ => self.part(n, input).map(Report::new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::solution::Solution;
+    use super::{compute, Day, Plan, Fail};
+
+    #[test]
+    fn fails() {
+        assert_eq!(compute(vec![1, 1, 3], 3), Err(Fail::NonDiv));
+        assert_eq!(compute(vec![1, 1, 4], 3), Err(Fail::Unsat));
+        assert_eq!(compute(vec![1, 2, 3], 3), Err(Fail::Unsat));
+        assert_eq!(compute(vec![1, 2, 3], 3), Err(Fail::Unsat));
+        assert_eq!(compute(vec![2, 2, 2, 3], 3), Err(Fail::Unsat));
+    }
+
+    #[test]
+    fn simple() {
+        assert_eq!(compute(vec![3, 3, 3], 3), Ok(Plan {
+            front: vec![3],
+            back: vec![vec![3], vec![3]],
+            ent: 3
+        }));
+        assert_eq!(compute(vec![2, 3, 2, 3, 2, 3], 3), Ok(Plan {
+            front: vec![3, 2],
+            back: vec![vec![3, 2], vec![3, 2]],
+            ent: 6
+        }));
+    }
+
+    #[test]
+    fn example() {
+        let plan = compute(vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11], 3).unwrap();
+        assert_eq!(plan.ent, 99);
+        assert_eq!(plan.front, &[11, 9]);
+        // The problem-setter's combination enumerator seems to go
+        // left-to-right and with-then-without; mine is right-to-left
+        // and without-then-with, so it doesn't match.
+        for bag in plan.back {
+            assert_eq!(bag.iter().fold(0, |a, &b| a + b), 20)
+        }
+    }
+
+    #[test]
+    fn oddsize() {
+        assert_eq!(compute(vec![1, 1, 2], 2), Ok(Plan {
+            ent: 2,
+            front: vec![2],
+            back: vec![vec![1, 1]],
+        }));
+        assert_eq!(compute(vec![1, 1, 1, 1, 1, 1, 2], 4), Ok(Plan {
+            ent: 2,
+            front: vec![2],
+            back: vec![vec![1, 1], vec![1, 1], vec![1, 1]],
+        }));
+    }
+
+    #[test]
+    fn example2() {
+        let plan = compute(vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11], 4).unwrap();
+        assert_eq!(plan.ent, 44);
+        assert_eq!(plan.front, &[11, 4]);
+        for bag in plan.back {
+            assert_eq!(bag.iter().fold(0, |a, &b| a + b), 15)
+        }
+    }
+
+    #[test]
+    fn report() {
+        let input = "1\n2\n3\n4\n5\n7\n8\n9\n10\n11\n";
+        let report = Day.report(2, input).unwrap();
+        assert_eq!(report.answer, "44");
+        assert_eq!(report.witness.unwrap().to_string(),
+                   "{\"front\":[11,4],\"back\":[[9,3,2,1],[10,5],[8,7]],\"entanglement\":44}");
+    }
+}
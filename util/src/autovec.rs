@@ -1,10 +1,35 @@
+use std::cmp::max;
+use std::error::Error;
+use std::fmt;
 use std::ops::Deref;
 
+// Grows to fit whatever index is asked for, filling in with
+// `T::default()`.  A cap, if any, is the largest length it'll grow to.
 #[derive(Debug, Clone)]
-pub struct AutoVec<T: Default>(Vec<T>);
+pub struct AutoVec<T: Default>(Vec<T>, Option<usize>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooBig {
+    pub index: usize,
+    pub cap: usize,
+}
+impl fmt::Display for TooBig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "index {} is past the cap of {}", self.index, self.cap)
+    }
+}
+impl Error for TooBig { }
+
+fn check_cap(i: usize, cap: Option<usize>) -> Result<(), TooBig> {
+    match cap {
+        Some(cap) if i >= cap => Err(TooBig { index: i, cap }),
+        _ => Ok(())
+    }
+}
 
 impl<T: Default> AutoVec<T> {
-    pub fn new() -> Self { AutoVec(Vec::new()) }
+    pub fn new() -> Self { AutoVec(Vec::new(), None) }
+    pub fn with_cap(cap: usize) -> Self { AutoVec(Vec::new(), Some(cap)) }
     fn ensure_len(&mut self, len: usize) {
         while len > self.0.len() {
             self.0.push(T::default());
@@ -14,9 +39,13 @@ impl<T: Default> AutoVec<T> {
         self.ensure_len(len);
         self.0.into_boxed_slice()
     }
-    pub fn at(&mut self, i: usize) -> &mut T {
+    pub fn try_at(&mut self, i: usize) -> Result<&mut T, TooBig> {
+        check_cap(i, self.1)?;
         self.ensure_len(i + 1);
-        &mut self.0[i]
+        Ok(&mut self.0[i])
+    }
+    pub fn at(&mut self, i: usize) -> &mut T {
+        self.try_at(i).unwrap_or_else(|e| panic!("AutoVec: {}", e))
     }
 }
 
@@ -41,9 +70,61 @@ impl <T: Default> IntoIterator for AutoVec<T> {
     }
 }
 
+// The two-dimensional version: `at(r, c)` grows both ways, and the
+// extents are tracked so it can be finished off as a rectangle (or a
+// square) even if some rows were never touched past their start.
+#[derive(Debug, Clone)]
+pub struct AutoGrid<T: Default> {
+    rows: AutoVec<AutoVec<T>>,
+    cols: usize,
+    cap: Option<usize>,
+}
+pub type Boxed<T> = Box<[Box<[T]>]>;
+
+impl<T: Default> AutoGrid<T> {
+    pub fn new() -> Self { AutoGrid { rows: AutoVec::new(), cols: 0, cap: None } }
+    // The cap applies to each dimension separately.
+    pub fn with_cap(cap: usize) -> Self {
+        AutoGrid { rows: AutoVec::with_cap(cap), cols: 0, cap: Some(cap) }
+    }
+    pub fn rows(&self) -> usize { self.rows.len() }
+    pub fn cols(&self) -> usize { self.cols }
+    pub fn get(&self, r: usize, c: usize) -> Option<&T> {
+        self.rows.get(r).and_then(|row| row.get(c))
+    }
+    pub fn try_at(&mut self, r: usize, c: usize) -> Result<&mut T, TooBig> {
+        check_cap(c, self.cap)?;
+        let row = self.rows.try_at(r)?;
+        self.cols = max(self.cols, c + 1);
+        row.try_at(c)
+    }
+    pub fn at(&mut self, r: usize, c: usize) -> &mut T {
+        self.try_at(r, c).unwrap_or_else(|e| panic!("AutoGrid: {}", e))
+    }
+    fn finish(self, nr: usize, nc: usize) -> Boxed<T> {
+        self.rows.into_boxed_slice(nr).into_vec().into_iter().map(|row| {
+            row.into_boxed_slice(nc)
+        }).collect::<Vec<_>>().into_boxed_slice()
+    }
+    pub fn into_rect(self) -> Boxed<T> {
+        let (nr, nc) = (self.rows(), self.cols);
+        self.finish(nr, nc)
+    }
+    pub fn into_square(self) -> Boxed<T> {
+        let n = max(self.rows(), self.cols);
+        self.finish(n, n)
+    }
+}
+
+impl<T: Default> Default for AutoGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::AutoVec;
+    use super::{AutoVec, AutoGrid, TooBig};
 
     #[test]
     fn empty() {
@@ -88,4 +169,67 @@ mod tests {
         *v.at(3) = 1000;
         assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![0, 0, 0, 1000]);
     }
+
+    #[test]
+    fn capped() {
+        let mut v = AutoVec::with_cap(4);
+        *v.at(3) = 1;
+        assert_eq!(v.try_at(4).err(), Some(TooBig { index: 4, cap: 4 }));
+        assert_eq!(v.try_at(1 << 40).err(), Some(TooBig { index: 1 << 40, cap: 4 }));
+        assert_eq!(v.0, vec![0, 0, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 is past the cap of 2")]
+    fn capped_at() {
+        let mut v: AutoVec<u8> = AutoVec::with_cap(2);
+        v.at(5);
+    }
+
+    #[test]
+    fn grid_rect() {
+        let mut g = AutoGrid::new();
+        *g.at(0, 2) = 1;
+        *g.at(1, 0) = 2;
+        assert_eq!((g.rows(), g.cols()), (2, 3));
+        assert_eq!(g.get(1, 0), Some(&2));
+        assert_eq!(g.get(1, 2), None);
+        let r = g.into_rect();
+        assert_eq!(r, vec![vec![0, 0, 1].into_boxed_slice(),
+                           vec![2, 0, 0].into_boxed_slice()].into_boxed_slice());
+    }
+
+    #[test]
+    fn grid_square() {
+        let mut g = AutoGrid::new();
+        *g.at(0, 2) = 1;
+        let sq = g.clone().into_square();
+        assert_eq!(sq.len(), 3);
+        assert!(sq.iter().all(|row| row.len() == 3));
+        assert_eq!(sq[0][2], 1);
+        *g.at(3, 0) = 2;
+        let sq = g.into_square();
+        assert_eq!(sq.len(), 4);
+        assert!(sq.iter().all(|row| row.len() == 4));
+        assert_eq!(sq[3][0], 2);
+    }
+
+    #[test]
+    fn grid_empty() {
+        let g: AutoGrid<i32> = AutoGrid::new();
+        assert_eq!(g.clone().into_rect().len(), 0);
+        assert_eq!(g.into_square().len(), 0);
+    }
+
+    #[test]
+    fn grid_capped() {
+        let mut g = AutoGrid::with_cap(3);
+        *g.at(2, 2) = 1;
+        assert_eq!(g.try_at(3, 0).err(), Some(TooBig { index: 3, cap: 3 }));
+        assert_eq!(g.try_at(0, 3).err(), Some(TooBig { index: 3, cap: 3 }));
+        // Failed attempts don't grow anything.
+        assert_eq!((g.rows(), g.cols()), (3, 3));
+        assert_eq!(g.get(2, 2), Some(&1));
+        assert_eq!(g.get(0, 0), None);
+    }
 }
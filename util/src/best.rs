@@ -1,4 +1,6 @@
 use std::borrow::{Borrow,ToOwned};
+use std::fmt;
+use std::slice;
 
 // Maybe this is a bad idea and I should just write `Copy` directly but:
 pub trait Score: Copy { }
@@ -8,24 +10,42 @@ pub trait Cmp<S: Score> {
     fn better(&self, old: S, shiny: S) -> bool;
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Smallest;
 impl<S: Score + Ord> Cmp<S> for Smallest {
     fn better(&self, old: S, shiny: S) -> bool { shiny < old }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Largest;
 impl<S: Score + Ord> Cmp<S> for Largest {
     fn better(&self, old: S, shiny: S) -> bool { shiny > old }
 }
 
+// How a search fared: offers taken or turned away by `add`, and
+// branches cut off by `can_win`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    pub accepted: u64,
+    pub rejected: u64,
+    pub pruned: u64,
+}
+impl fmt::Display for Counts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} accepted, {} rejected, {} pruned", self.accepted, self.rejected, self.pruned)
+    }
+}
+
+#[derive(Clone)]
 pub struct Best<S: Score, T, C: Cmp<S>> {
     best: Option<(S, T)>,
     cmp: C,
+    counts: Counts,
 }
 impl<S: Score, T, C: Cmp<S>> Best<S, T, C> {
     // FIXME: have a () version of this when C doesn't need inferred (& is Default or w/e)
     pub fn new(cmp: C) -> Self {
-        Best { best: None, cmp: cmp }
+        Best { best: None, cmp: cmp, counts: Counts::default() }
     }
     pub fn add<Q: ?Sized>(&mut self, offer: S, thing: &Q)
         where T: Borrow<Q>,
@@ -35,8 +55,50 @@ impl<S: Score, T, C: Cmp<S>> Best<S, T, C> {
             &Some((so_far, _)) => self.cmp.better(so_far, offer)
         };
         if better {
+            self.counts.accepted += 1;
             self.best = Some((offer, thing.to_owned()));
+        } else {
+            self.counts.rejected += 1;
+        }
+    }
+    // For branch and bound: `bound` is the best score that anything
+    // under the current branch could possibly get, so if even that
+    // wouldn't be accepted by `add` then the branch can be skipped.
+    // The bound has to be optimistic (never worse than the truth), or
+    // this will throw away winners.
+    pub fn can_win(&mut self, bound: S) -> bool {
+        let hope = match self.best {
+            None => true,
+            Some((so_far, _)) => self.cmp.better(so_far, bound)
+        };
+        if !hope {
+            self.counts.pruned += 1;
         }
+        hope
+    }
+    pub fn counts(&self) -> Counts {
+        self.counts
+    }
+    // A copy to carry on searching from somewhere else, with nothing
+    // counted yet; `merge` brings it back.
+    pub fn fork(&self) -> Self where T: Clone, C: Clone {
+        Best { best: self.best.clone(), cmp: self.cmp.clone(), counts: Counts::default() }
+    }
+    // As if everything offered to `other` had been offered here, after
+    // what this has already seen: its best only wins if strictly better.
+    pub fn merge(&mut self, other: Self) {
+        if let Some((offer, thing)) = other.best {
+            let better = match self.best {
+                None => true,
+                Some((so_far, _)) => self.cmp.better(so_far, offer)
+            };
+            if better {
+                self.best = Some((offer, thing));
+            }
+        }
+        self.counts.accepted += other.counts.accepted;
+        self.counts.rejected += other.counts.rejected;
+        self.counts.pruned += other.counts.pruned;
     }
     pub fn get(&self) -> Option<(S, &T)> {
         self.best.as_ref().map(|&(s, ref t)| (s, t))
@@ -52,9 +114,113 @@ impl<S: Score, T, C: Cmp<S>> Best<S, T, C> {
     }
 }
 
+// Keeps the K best offers, best first.  Ties are broken in favor of
+// whichever came first, as with `Best`, so an offer only gets in if
+// it's strictly better than something already held (or there's room).
+//
+// They're kept in a sorted `Vec`, not a heap, so an offer costs O(k)
+// instead of O(log k).  K is small everywhere this is used, and `get`
+// and `iter` want them in order anyway, which a heap would have to sort
+// every time.
+pub struct BestK<S: Score, T, C: Cmp<S>> {
+    best: Vec<(S, T)>,
+    k: usize,
+    cmp: C,
+}
+impl<S: Score, T, C: Cmp<S>> BestK<S, T, C> {
+    pub fn new(k: usize, cmp: C) -> Self {
+        BestK { best: Vec::with_capacity(k), k, cmp }
+    }
+    pub fn add<Q>(&mut self, offer: S, thing: &Q)
+        where T: Borrow<Q>,
+              Q: ?Sized + ToOwned<Owned = T> {
+        let cmp = &self.cmp;
+        let pos = self.best.iter().position(|&(so_far, _)| cmp.better(so_far, offer))
+            .unwrap_or(self.best.len());
+        if pos < self.k {
+            if self.best.len() == self.k {
+                let _worst = self.best.pop();
+            }
+            self.best.insert(pos, (offer, thing.to_owned()));
+        }
+    }
+    pub fn get(&self) -> Option<(S, &T)> {
+        self.best.first().map(|&(s, ref t)| (s, t))
+    }
+    pub fn iter(&self) -> Iter<'_, S, T> {
+        Iter(self.best.iter())
+    }
+    pub fn len(&self) -> usize {
+        self.best.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.best.is_empty()
+    }
+    pub fn finish(self) -> Vec<(S, T)> {
+        self.best
+    }
+}
+
+pub struct Iter<'b, S: Score + 'b, T: 'b>(slice::Iter<'b, (S, T)>);
+impl<'b, S: Score, T> Iterator for Iter<'b, S, T> {
+    type Item = (S, &'b T);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|&(s, ref t)| (s, t))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+// Keeps every offer tied for best, in the order they were offered.
+// "Tied" means neither is better than the other, so a custom `Cmp`
+// decides what counts as equal.
+pub struct AllBest<S: Score, T, C: Cmp<S>> {
+    best: Option<(S, Vec<T>)>,
+    cmp: C,
+}
+impl<S: Score, T, C: Cmp<S>> AllBest<S, T, C> {
+    pub fn new(cmp: C) -> Self {
+        AllBest { best: None, cmp }
+    }
+    pub fn add<Q>(&mut self, offer: S, thing: &Q)
+        where T: Borrow<Q>,
+              Q: ?Sized + ToOwned<Owned = T> {
+        if let Some((so_far, ref mut things)) = self.best {
+            if self.cmp.better(so_far, offer) {
+                // Fall through to replace.
+            } else if !self.cmp.better(offer, so_far) {
+                things.push(thing.to_owned());
+                return;
+            } else {
+                return;
+            }
+        }
+        self.best = Some((offer, vec![thing.to_owned()]));
+    }
+    pub fn get(&self) -> Option<(S, &[T])> {
+        self.best.as_ref().map(|&(s, ref ts)| (s, ts as &[T]))
+    }
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        match self.best {
+            Some((_, ref ts)) => ts.iter(),
+            None => [].iter(),
+        }
+    }
+    pub fn finish(self) -> Option<(S, Vec<T>)> {
+        self.best
+    }
+    pub fn unwrap(self) -> (S, Vec<T>) {
+        self.best.unwrap()
+    }
+    pub fn expect(self, msg: &str) -> (S, Vec<T>) {
+        self.best.expect(msg)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Best, Smallest, Largest, Cmp};
+    use super::{Best, BestK, AllBest, Counts, Smallest, Largest, Cmp};
 
     #[test]
     fn empty() {
@@ -139,4 +305,156 @@ mod tests {
         b.add(9, &42);
         assert_eq!(b.get(), Some((7, &17)));
     }
+
+    #[test]
+    fn can_win_empty() {
+        let mut b: Best<i32, i32, _> = Best::new(Smallest);
+        assert!(b.can_win(1000));
+        assert_eq!(b.counts(), Counts::default());
+    }
+
+    #[test]
+    fn can_win_prune() {
+        let mut b = Best::new(Smallest);
+        b.add(17, &23);
+        assert!(b.can_win(16));
+        assert!(!b.can_win(17));
+        assert!(!b.can_win(18));
+        assert_eq!(b.counts(), Counts { accepted: 1, rejected: 0, pruned: 2 });
+    }
+
+    #[test]
+    fn can_win_larger() {
+        let mut b = Best::new(Largest);
+        b.add(17, &23);
+        assert!(b.can_win(18));
+        assert!(!b.can_win(5));
+        assert_eq!(b.counts().pruned, 1);
+    }
+
+    #[test]
+    fn counts() {
+        let mut b = Best::new(Smallest);
+        b.add(17, &23);
+        b.add(17, &46);
+        b.add(42, &46);
+        b.add(5, &46);
+        assert_eq!(b.counts(), Counts { accepted: 2, rejected: 2, pruned: 0 });
+        assert_eq!(b.counts().to_string(), "2 accepted, 2 rejected, 0 pruned");
+    }
+
+    #[test]
+    fn fork_merge() {
+        let mut b = Best::new(Smallest);
+        b.add(17, &23);
+        let mut f = b.fork();
+        assert_eq!(f.get(), Some((17, &23)));
+        assert_eq!(f.counts(), Counts::default());
+        f.add(17, &46);
+        assert!(!f.can_win(20));
+        b.merge(f);
+        assert_eq!(b.get(), Some((17, &23)));
+        let mut f = b.fork();
+        f.add(5, &99);
+        b.merge(f);
+        assert_eq!(b.get(), Some((5, &99)));
+        assert_eq!(b.counts(), Counts { accepted: 2, rejected: 1, pruned: 1 });
+        let mut e = Best::new(Smallest);
+        e.merge(b);
+        assert_eq!(e.unwrap(), (5, 99));
+    }
+
+    #[test]
+    fn k_empty() {
+        let b: BestK<i32, i32, _> = BestK::new(3, Smallest);
+        assert!(b.get().is_none());
+        assert!(b.is_empty());
+        assert_eq!(b.finish(), vec![]);
+    }
+
+    #[test]
+    fn k_zero() {
+        let mut b = BestK::new(0, Smallest);
+        b.add(17, &23);
+        assert!(b.get().is_none());
+        assert_eq!(b.finish(), vec![]);
+    }
+
+    #[test]
+    fn k_order() {
+        let mut b = BestK::new(3, Smallest);
+        b.add(17, &1);
+        b.add(5, &2);
+        b.add(42, &3);
+        assert_eq!(b.get(), Some((5, &2)));
+        assert_eq!(b.iter().collect::<Vec<_>>(), vec![(5, &2), (17, &1), (42, &3)]);
+        b.add(9, &4);
+        assert_eq!(b.len(), 3);
+        assert_eq!(b.finish(), vec![(5, 2), (9, 4), (17, 1)]);
+    }
+
+    #[test]
+    fn k_worse() {
+        let mut b = BestK::new(2, Largest);
+        b.add(17, &1);
+        b.add(23, &2);
+        b.add(5, &3);
+        assert_eq!(b.finish(), vec![(23, 2), (17, 1)]);
+    }
+
+    #[test]
+    fn k_equal() {
+        let mut b = BestK::new(2, Smallest);
+        b.add(17, &1);
+        b.add(17, &2);
+        b.add(17, &3);
+        assert_eq!(b.finish(), vec![(17, 1), (17, 2)]);
+    }
+
+    #[test]
+    fn k_custom() {
+        let mut b = BestK::new(2, MoreBits);
+        b.add(8, &1);
+        b.add(7, &2);
+        b.add(5, &3);
+        b.add(6, &4);
+        assert_eq!(b.finish(), vec![(7, 2), (5, 3)]);
+    }
+
+    #[test]
+    fn all_empty() {
+        let b: AllBest<i32, i32, _> = AllBest::new(Smallest);
+        assert!(b.get().is_none());
+        assert_eq!(b.iter().count(), 0);
+        assert!(b.finish().is_none());
+    }
+
+    #[test] #[should_panic(expected = "insert message here")]
+    fn all_empty_expect() {
+        let b: AllBest<i32, i32, _> = AllBest::new(Smallest);
+        let _ = b.expect("insert message here");
+    }
+
+    #[test]
+    fn all_ties() {
+        let mut b = AllBest::new(Smallest);
+        b.add(17, &23);
+        b.add(17, &46);
+        assert_eq!(b.get(), Some((17, &[23, 46] as &[_])));
+        b.add(42, &5);
+        assert_eq!(b.iter().collect::<Vec<_>>(), vec![&23, &46]);
+        b.add(5, &99);
+        assert_eq!(b.unwrap(), (5, vec![99]));
+    }
+
+    #[test]
+    fn all_custom() {
+        let mut b = AllBest::new(MoreBits);
+        b.add(8, &1);
+        b.add(7, &2);
+        b.add(6, &3);
+        b.add(11, &4);
+        b.add(9, &5);
+        assert_eq!(b.unwrap(), (7, vec![2, 4]));
+    }
 }
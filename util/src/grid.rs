@@ -0,0 +1,271 @@
+use std::fmt;
+use std::ops::{Index, IndexMut, Range};
+
+use input::{Input, LineError};
+
+// A dense rectangle of cells, addressed as (x, y) with x going across
+// and y going down, stored row by row.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// Which cells count as next to each other: just the four sharing an
+// edge, or the diagonals too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conn {
+    Four,
+    Eight,
+}
+
+// What's past the edge: nothing (neighbours there are skipped), or the
+// other side of the grid.  Wrapping a grid less than 3 wide or high can
+// give the same neighbour twice, or the cell itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Clip,
+    Wrap,
+}
+
+const FOUR: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const EIGHT: [(isize, isize); 8] =
+    [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(x, y));
+            }
+        }
+        Grid { width, height, cells }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn contains(&self, x: usize, y: usize) -> bool { x < self.width && y < self.height }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains(x, y) { Some(&self.cells[y * self.width + x]) } else { None }
+    }
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) { Some(&mut self.cells[y * self.width + x]) } else { None }
+    }
+
+    // Every cell, row by row.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, T> { self.cells.iter() }
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<'_, T> { self.cells.iter_mut() }
+    pub fn positions(&self) -> impl Iterator<Item=(usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        // (`chunks` won't take 0, but then there are no cells anyway.)
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // The cells in a rectangle, row by row; it has to fit in the grid.
+    pub fn rect(&self, xs: Range<usize>, ys: Range<usize>) -> impl Iterator<Item=&T> {
+        self.check_rect(&xs, &ys);
+        let width = self.width;
+        self.cells[ys.start * width..ys.end * width].chunks(width.max(1))
+            .flat_map(move |row| row[xs.clone()].iter())
+    }
+    pub fn rect_mut(&mut self, xs: Range<usize>, ys: Range<usize>) -> impl Iterator<Item=&mut T> {
+        self.check_rect(&xs, &ys);
+        let width = self.width;
+        self.cells[ys.start * width..ys.end * width].chunks_mut(width.max(1))
+            .flat_map(move |row| row[xs.clone()].iter_mut())
+    }
+    fn check_rect(&self, xs: &Range<usize>, ys: &Range<usize>) {
+        assert!(xs.start <= xs.end && xs.end <= self.width &&
+                ys.start <= ys.end && ys.end <= self.height,
+                "rectangle {:?} x {:?} isn't inside a {}x{} grid", xs, ys, self.width, self.height);
+    }
+
+    pub fn neighbours(&self, x: usize, y: usize, conn: Conn, edge: Edge)
+                      -> impl Iterator<Item=(usize, usize)> {
+        let offsets: &'static [(isize, isize)] = match conn {
+            Conn::Four => &FOUR,
+            Conn::Eight => &EIGHT,
+        };
+        let (w, h) = (self.width as isize, self.height as isize);
+        let (x, y) = (x as isize, y as isize);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            match edge {
+                Edge::Clip if nx < 0 || ny < 0 || nx >= w || ny >= h => None,
+                Edge::Clip => Some((nx as usize, ny as usize)),
+                Edge::Wrap => Some((nx.rem_euclid(w) as usize, ny.rem_euclid(h) as usize)),
+            }
+        })
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    // One row per line; blank lines are skipped, and the rest all have
+    // to be the same width.
+    pub fn parse_with<F>(text: &str, mut f: F) -> Result<Self, LineError>
+        where F: FnMut(char) -> Option<T> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in Input::text(text).lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let before = cells.len();
+            for (i, c) in line.chars().enumerate() {
+                cells.push(f(c).ok_or_else(|| {
+                    LineError::at(line.num, i + 1, format!("unexpected character {:?}", c))
+                })?);
+            }
+            let this_width = cells.len() - before;
+            match width {
+                None => width = Some(this_width),
+                Some(w) if w != this_width => {
+                    return Err(line.error(format!("{} cells wide, but the first row was {}",
+                                                  this_width, w)));
+                }
+                Some(_) => (),
+            }
+            height += 1;
+        }
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn render_with<F: FnMut(&T) -> char>(&self, mut f: F) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&mut f));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+// The usual puzzle picture: `#` for on, `.` for off.
+impl Grid<bool> {
+    pub fn parse(text: &str) -> Result<Self, LineError> {
+        Grid::parse_with(text, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+    }
+    pub fn render(&self) -> String {
+        self.render_with(|&b| if b { '#' } else { '.' })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!("({}, {}) is outside a {}x{} grid", x, y, self.width, self.height)
+        })
+    }
+}
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (w, h) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| {
+            panic!("({}, {}) is outside a {}x{} grid", x, y, w, h)
+        })
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Grid({}x{}) ", self.width, self.height)?;
+        f.debug_list().entries(self.rows()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Conn, Edge};
+
+    #[test]
+    fn basics() {
+        let mut g = Grid::from_fn(3, 2, |x, y| x + 10 * y);
+        assert_eq!((g.width(), g.height()), (3, 2));
+        assert_eq!(g[(2, 1)], 12);
+        assert_eq!(g.get(3, 0), None);
+        assert_eq!(g.get(0, 2), None);
+        g[(0, 1)] = 99;
+        assert_eq!(g.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 99, 11, 12]);
+        assert_eq!(g.positions().nth(4), Some((1, 1)));
+        assert_eq!(g.rows().collect::<Vec<_>>(), vec![&[0, 1, 2][..], &[99, 11, 12][..]]);
+        assert_eq!(Grid::new(2, 2, 'x').map(|&c| c == 'x'), Grid::new(2, 2, true));
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside a 3x2 grid")]
+    fn out_of_bounds() {
+        let _ = Grid::new(3, 2, 0)[(3, 0)];
+    }
+
+    #[test]
+    fn rects() {
+        let mut g = Grid::from_fn(4, 3, |x, y| x + 10 * y);
+        assert_eq!(g.rect(1..3, 1..3).cloned().collect::<Vec<_>>(), vec![11, 12, 21, 22]);
+        assert_eq!(g.rect(2..2, 0..3).count(), 0);
+        for c in g.rect_mut(0..4, 2..3) {
+            *c = 0;
+        }
+        assert_eq!(g.iter().filter(|&&c| c == 0).count(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't inside a 4x3 grid")]
+    fn rect_too_big() {
+        Grid::new(4, 3, 0).rect(0..5, 0..1).count();
+    }
+
+    #[test]
+    fn neighbours() {
+        let g = Grid::new(3, 3, ());
+        let nbrs = |x, y, conn, edge| g.neighbours(x, y, conn, edge).collect::<Vec<_>>();
+        assert_eq!(nbrs(1, 1, Conn::Four, Edge::Clip), vec![(1, 0), (0, 1), (2, 1), (1, 2)]);
+        assert_eq!(nbrs(1, 1, Conn::Eight, Edge::Clip).len(), 8);
+        assert_eq!(nbrs(0, 0, Conn::Four, Edge::Clip), vec![(1, 0), (0, 1)]);
+        assert_eq!(nbrs(0, 0, Conn::Eight, Edge::Clip), vec![(1, 0), (0, 1), (1, 1)]);
+        assert_eq!(nbrs(2, 2, Conn::Eight, Edge::Clip), vec![(1, 1), (2, 1), (1, 2)]);
+        assert_eq!(nbrs(0, 0, Conn::Four, Edge::Wrap), vec![(0, 2), (2, 0), (1, 0), (0, 1)]);
+        assert_eq!(nbrs(0, 2, Conn::Eight, Edge::Wrap),
+                   vec![(2, 1), (0, 1), (1, 1), (2, 2), (1, 2), (2, 0), (0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let text = ".#.\n##.\n\n";
+        let g = Grid::parse(text).unwrap();
+        assert_eq!((g.width(), g.height()), (3, 2));
+        assert!(g[(1, 0)] && g[(0, 1)] && !g[(2, 1)]);
+        assert_eq!(g.render(), ".#.\n##.\n");
+        let digits = Grid::parse_with("12\n34\n", |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits.render_with(|&d| (b'0' + d as u8 * 2) as char), "24\n68\n");
+        assert_eq!(Grid::parse("").unwrap(), Grid::new(0, 0, false));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Grid::parse("..#\n.x.\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unexpected character 'x'");
+        let err = Grid::parse("..#\n...\n..\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 3: 2 cells wide, but the first row was 3");
+    }
+}
@@ -0,0 +1,275 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, stdin, BufRead, BufReader};
+use std::ops::Deref;
+use std::path::Path;
+
+use scan::{scan, Scanner};
+
+// Puzzle input, from stdin or a file, as numbered lines with trailing
+// whitespace (including any `\r`) already stripped.
+pub struct Input<'a>(Box<dyn BufRead + 'a>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub num: usize, // 1-based
+    pub text: String,
+}
+
+// Columns are 1-based and count chars, not bytes; `None` means the
+// problem is with the line as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl LineError {
+    pub fn new<M: Into<String>>(line: usize, message: M) -> Self {
+        LineError { line, column: None, message: message.into() }
+    }
+    pub fn at<M: Into<String>>(line: usize, column: usize, message: M) -> Self {
+        LineError { line, column: Some(column), message: message.into() }
+    }
+}
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.column {
+            None => write!(f, "line {}: {}", self.line, self.message),
+            Some(col) => write!(f, "line {}, column {}: {}", self.line, col, self.message),
+        }
+    }
+}
+impl Error for LineError { }
+
+impl Line {
+    pub fn error<M: Into<String>>(&self, message: M) -> LineError {
+        LineError::new(self.num, message)
+    }
+    // `sub` has to be a slice of `self.text`, like a word from `split`.
+    pub fn error_at<M: Into<String>>(&self, sub: &str, message: M) -> LineError {
+        LineError::at(self.num, self.column_of(sub), message)
+    }
+    pub fn column_of(&self, sub: &str) -> usize {
+        let base = self.text.as_ptr() as usize;
+        let ptr = sub.as_ptr() as usize;
+        assert!(ptr >= base && ptr + sub.len() <= base + self.text.len(),
+                "column_of: {:?} isn't part of line {}", sub, self.num);
+        self.text[..ptr - base].chars().count() + 1
+    }
+}
+impl Deref for Line {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<'a> Input<'a> {
+    pub fn stdin() -> Input<'static> {
+        Input(Box::new(stdin().lock()))
+    }
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Input<'static>> {
+        Ok(Input(Box::new(BufReader::new(File::open(path)?))))
+    }
+    // For a command-line argument: missing or "-" means stdin.
+    pub fn from_arg<S: AsRef<str>>(arg: Option<S>) -> io::Result<Input<'static>> {
+        match arg {
+            None => Ok(Input::stdin()),
+            Some(ref s) if s.as_ref() == "-" => Ok(Input::stdin()),
+            Some(s) => Input::open(s.as_ref()),
+        }
+    }
+    pub fn from_reader<B: BufRead + 'a>(b: B) -> Input<'a> {
+        Input(Box::new(b))
+    }
+    pub fn text(s: &'a str) -> Input<'a> {
+        Input::from_reader(s.as_bytes())
+    }
+
+    pub fn lines(self) -> Lines<'a> {
+        Lines { inner: self.0.lines(), num: 0 }
+    }
+    pub fn read_lines(self) -> Result<Vec<Line>, LineError> {
+        self.lines().collect()
+    }
+    // The whole input as one string, stripped line by line, with every
+    // line (even the last) ending in a newline.
+    pub fn read_text(self) -> Result<String, LineError> {
+        let mut text = String::new();
+        for line in self.lines() {
+            text.push_str(&line?);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+    // Runs of blank lines separate sections; empty sections aren't
+    // returned, so leading or doubled blank lines don't matter.
+    pub fn sections(self) -> Result<Vec<Vec<Line>>, LineError> {
+        let mut sections = vec![];
+        let mut current = vec![];
+        for line in self.lines() {
+            let line = line?;
+            if line.text.is_empty() {
+                if !current.is_empty() {
+                    sections.push(current);
+                    current = vec![];
+                }
+            } else {
+                current.push(line);
+            }
+        }
+        if !current.is_empty() {
+            sections.push(current);
+        }
+        Ok(sections)
+    }
+    // Parses each line with `f`, stopping at the first error and
+    // tagging it with the line number.
+    pub fn parse_lines<T, E, F>(self, mut f: F) -> Result<Vec<T>, LineError>
+        where E: fmt::Display, F: FnMut(&str) -> Result<T, E> {
+        self.lines().map(|line| {
+            let line = line?;
+            f(&line).map_err(|e| line.error(e.to_string()))
+        }).collect()
+    }
+    // Runs a `scan` grammar over each line; it has to use the whole line.
+    pub fn scan_lines<T, F>(self, mut f: F) -> Result<Vec<T>, LineError>
+        where F: FnMut(&mut Scanner) -> Result<T, LineError> {
+        self.lines().map(|line| {
+            let line = line?;
+            scan(line.num, &line.text, &mut f)
+        }).collect()
+    }
+    // Lines like "Hit Points: 104", as the key/value pairs a day would
+    // take on its command line ("boss Hit Points", "104" with a `prefix`
+    // of "boss"); blank lines are skipped.
+    pub fn key_value_args(self, prefix: &str) -> Result<Vec<String>, LineError> {
+        let mut args = vec![];
+        for line in self.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let mut kv = line.splitn(2, ':');
+            let key = kv.next().unwrap();
+            let value = kv.next().ok_or_else(|| line.error("expected \"name: value\""))?;
+            args.push(format!("{} {}", prefix, key));
+            args.push(value.trim().to_owned());
+        }
+        Ok(args)
+    }
+}
+
+pub struct Lines<'a> {
+    inner: io::Lines<Box<dyn BufRead + 'a>>,
+    num: usize,
+}
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Line, LineError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|rtext| {
+            self.num += 1;
+            let mut text = rtext.map_err(|e| LineError::new(self.num, format!("I/O error: {}", e)))?;
+            let len = text.trim_end().len();
+            text.truncate(len);
+            Ok(Line { num: self.num, text })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Input, Line, LineError};
+
+    fn input(s: &str) -> Input<'_> {
+        Input::text(s)
+    }
+    fn texts(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|l| &l.text as &str).collect()
+    }
+
+    #[test]
+    fn numbered() {
+        let lines = input("a\nb\n\nc").read_lines().unwrap();
+        assert_eq!(lines.iter().map(|l| l.num).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(texts(&lines), vec!["a", "b", "", "c"]);
+    }
+
+    #[test]
+    fn stripped() {
+        let lines = input("a b  \r\n\t\r\nc\r\n").read_lines().unwrap();
+        assert_eq!(texts(&lines), vec!["a b", "", "c"]);
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(input("a  \r\n\nb").read_text().unwrap(), "a\n\nb\n");
+        assert_eq!(input("").read_text().unwrap(), "");
+    }
+
+    #[test]
+    fn sections() {
+        let secs = input("\na\nb\n\n\n  \nc\n\n").sections().unwrap();
+        assert_eq!(secs.len(), 2);
+        assert_eq!(texts(&secs[0]), vec!["a", "b"]);
+        assert_eq!(texts(&secs[1]), vec!["c"]);
+        assert_eq!(secs[1][0].num, 7);
+        assert_eq!(input("").sections().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(input("1\n2\n3\n").parse_lines(|s| s.parse::<u8>()), Ok(vec![1, 2, 3]));
+        let err = input("1\n2\nthree\n4").parse_lines(|s| s.parse::<u8>()).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, None);
+        assert_eq!(err.to_string(), "line 3: invalid digit found in string");
+    }
+
+    #[test]
+    fn key_value_args() {
+        assert_eq!(input("Hit Points: 104\n\nDamage:8\n").key_value_args("boss").unwrap(),
+                   vec!["boss Hit Points", "104", "boss Damage", "8"]);
+        let err = input("Hit Points: 104\nArmor 1\n").key_value_args("boss").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected \"name: value\"");
+    }
+
+    #[test]
+    fn columns() {
+        let line = Line { num: 5, text: "tö be or".to_owned() };
+        let words: Vec<_> = line.split(' ').collect();
+        assert_eq!(line.column_of(words[0]), 1);
+        assert_eq!(line.column_of(words[1]), 4);
+        assert_eq!(line.column_of(words[2]), 7);
+        assert_eq!(line.column_of(&line[line.len()..]), 9);
+        let err = line.error_at(words[2], "not to be");
+        assert_eq!(err, LineError::at(5, 7, "not to be"));
+        assert_eq!(err.to_string(), "line 5, column 7: not to be");
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't part of line")]
+    fn column_elsewhere() {
+        let line = Line { num: 1, text: "abc".to_owned() };
+        line.column_of("abc");
+    }
+
+    #[test]
+    fn io_error() {
+        let bad: &'static [u8] = b"ok\n\xff\n";
+        let err = Input::from_reader(bad).read_lines().unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.starts_with("I/O error"), "{}", err.message);
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(Input::open("/nonexistent/input.txt").is_err());
+        assert!(Input::from_arg(Some("/nonexistent/input.txt")).is_err());
+        assert!(Input::from_arg(Some("-")).is_ok());
+        assert!(Input::from_arg(None::<String>).is_ok());
+    }
+}
@@ -1,43 +1,200 @@
+use std::cell::RefCell;
+use std::iter::FusedIterator;
 use std::mem;
 
-pub struct LazyIter<I: Iterator, F: FnOnce() -> I>(State<I, F>);
+// An iterator that isn't built until something asks it for an item.
+//
+// `size_hint` doesn't force it, and knows nothing until something else
+// has; `len` (if the inner iterator has one) does force it, so ask for
+// that instead of `size_hint` to get an exact answer up front.  That's
+// why the state lives in a `RefCell`.  Once the inner iterator runs out
+// it's dropped, and this keeps returning `None`.
+pub struct LazyIter<I: Iterator, F: FnOnce() -> I>(RefCell<State<I, F>>);
 
 enum State<I, F> where I: Iterator, F: FnOnce() -> I {
     Future(F),
+    // Only seen if `F` panicked partway through.  (`next` takes
+    // `&mut self`, so it can't be reentered from inside `F`.)
     Present,
     Past(I),
     Done,
 }
+impl<I, F> State<I, F> where I: Iterator, F: FnOnce() -> I {
+    fn force(&mut self) -> &mut Self {
+        if let State::Future(_) = *self {
+            match mem::replace(self, State::Present) {
+                State::Future(f) => *self = State::Past(f()),
+                _ => unreachable!()
+            }
+        }
+        self
+    }
+    fn step<G>(&mut self, g: G) -> Option<I::Item> where G: FnOnce(&mut I) -> Option<I::Item> {
+        let rv = match *self.force() {
+            State::Future(_) => unreachable!(),
+            State::Present => panic!("LazyIter used after its initializer panicked"),
+            State::Past(ref mut inner) => g(inner),
+            State::Done => None,
+        };
+        if rv.is_none() {
+            // Drop the inner iterator.  This is important if
+            // it's the first half of a `Chain`.
+            *self = State::Done;
+        }
+        rv
+    }
+}
+
 impl<I, F> Iterator for LazyIter<I, F> where I: Iterator, F: FnOnce() -> I {
     type Item = I::Item;
     fn next(&mut self) -> Option<Self::Item> {
-        let early = match self.0 {
-            State::Done => Some(None),
-            State::Past(ref mut inner) => Some(inner.next()),
-            _ => None
-        };
-        if let Some(rv) = early {
-            if rv.is_none() {
-                // Drop the inner iterator.  This is important if
-                // it's the first half of a `Chain`.
-                self.0 = State::Done;
-            }
-            return rv;
+        self.0.get_mut().step(|inner| inner.next())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self.0.borrow() {
+            State::Past(ref inner) => inner.size_hint(),
+            State::Done => (0, Some(0)),
+            _ => (0, None),
         }
-        match mem::replace(&mut self.0, State::Present) {
-            State::Present => panic!("circular dependency in LazyIter"),
-            State::Future(f) => {
-                self.0 = State::Past(f());
-                self.next()
-            },
-            _ => unreachable!()
+    }
+}
+impl<I, F> DoubleEndedIterator for LazyIter<I, F>
+    where I: DoubleEndedIterator, F: FnOnce() -> I {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.get_mut().step(|inner| inner.next_back())
+    }
+}
+impl<I, F> ExactSizeIterator for LazyIter<I, F>
+    where I: ExactSizeIterator, F: FnOnce() -> I {
+    fn len(&self) -> usize {
+        match *self.0.borrow_mut().force() {
+            State::Past(ref inner) => inner.len(),
+            State::Done => 0,
+            _ => panic!("LazyIter used after its initializer panicked"),
         }
     }
 }
+impl<I, F> FusedIterator for LazyIter<I, F> where I: Iterator, F: FnOnce() -> I { }
+
 impl<I, F> LazyIter<I, F> where I: Iterator, F: FnOnce() -> I {
     pub fn new(f: F) -> LazyIter<I, F> {
-        LazyIter(State::Future(f))
+        LazyIter(RefCell::new(State::Future(f)))
+    }
+    pub fn is_forced(&self) -> bool {
+        matches!(*self.0.borrow(), State::Past(_) | State::Done)
     }
 }
 
-// FIXME: needs unit tests
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+    use super::LazyIter;
+
+    #[test]
+    fn lazy() {
+        let called = Cell::new(false);
+        let mut it = LazyIter::new(|| { called.set(true); 0..3 });
+        assert!(!called.get());
+        assert!(!it.is_forced());
+        assert_eq!(it.size_hint(), (0, None));
+        assert!(!called.get());
+        assert_eq!(it.next(), Some(0));
+        assert!(called.get());
+        assert!(it.is_forced());
+        assert_eq!(it.size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn exhaust() {
+        let it = LazyIter::new(|| 0..3);
+        assert_eq!(it.collect::<Vec<_>>(), vec![0, 1, 2]);
+        let mut it = LazyIter::new(|| 0..0);
+        assert_eq!(it.next(), None);
+        assert!(it.is_forced());
+        assert_eq!(it.size_hint(), (0, Some(0)));
+    }
+
+    // Holds a reference, so the count shows whether it's been dropped.
+    struct Canary(#[allow(dead_code)] Rc<()>, u32);
+    impl Iterator for Canary {
+        type Item = u32;
+        fn next(&mut self) -> Option<u32> {
+            if self.1 == 0 { None } else { self.1 -= 1; Some(self.1) }
+        }
+    }
+
+    #[test]
+    fn drop_when_done() {
+        let rc = Rc::new(());
+        let rc2 = rc.clone();
+        let mut it = LazyIter::new(move || Canary(rc2, 1));
+        assert_eq!(Rc::strong_count(&rc), 2);
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(Rc::strong_count(&rc), 2);
+        assert_eq!(it.next(), None);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn drop_in_chain() {
+        let rc = Rc::new(());
+        let rc2 = rc.clone();
+        let mut it = LazyIter::new(move || Canary(rc2, 1)).chain(0..2);
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    // Not fused: comes back to life after returning `None`.
+    struct Zombie(bool);
+    impl Iterator for Zombie {
+        type Item = ();
+        fn next(&mut self) -> Option<()> {
+            self.0 = !self.0;
+            if self.0 { None } else { Some(()) }
+        }
+    }
+
+    #[test]
+    fn fused() {
+        let mut it = LazyIter::new(|| Zombie(false));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut it = LazyIter::new(|| 0..4);
+        assert_eq!(it.next_back(), Some(3));
+        assert!(it.is_forced());
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.rev().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn len_forces() {
+        let called = Cell::new(false);
+        let mut it = LazyIter::new(|| { called.set(true); vec![1, 2, 3].into_iter() });
+        assert_eq!(it.len(), 3);
+        assert!(called.get());
+        assert!(it.is_forced());
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        let _ = it.next();
+        assert_eq!(it.len(), 2);
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!(it.len(), 0);
+    }
+
+    #[test]
+    fn poisoned() {
+        let mut it = LazyIter::new(|| -> ::std::ops::Range<u8> { panic!("oops") });
+        assert!(catch_unwind(AssertUnwindSafe(|| it.next())).is_err());
+        assert!(!it.is_forced());
+        let err = catch_unwind(AssertUnwindSafe(|| it.next())).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(),
+                   Some(&"LazyIter used after its initializer panicked"));
+    }
+}
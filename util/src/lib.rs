@@ -1,15 +1,29 @@
 pub mod autovec;
 pub mod best;
+pub mod grid;
+pub mod input;
+pub mod json;
 pub mod lazy_iter;
+pub mod memo;
+pub mod par;
+pub mod scan;
+pub mod search;
+pub mod solution;
 pub mod stack_set;
 pub mod subset_sum;
 pub mod symtab;
+pub mod walk;
 pub mod write_once;
 
-pub use autovec::AutoVec;
-pub use best::Best;
+pub use autovec::{AutoVec, AutoGrid};
+pub use best::{Best, BestK, AllBest};
+pub use grid::Grid;
+pub use input::Input;
+pub use json::Json;
 pub use lazy_iter::LazyIter;
+pub use memo::Memo;
 pub use stack_set::StackSet;
 pub use subset_sum::SubsetSumIter;
 pub use symtab::SymTab;
+pub use walk::Walk;
 pub use write_once::WriteOnce;
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A transposition table: remembers something about each state a search
+// has been through, so that reaching the same state by another route
+// can reuse it.  It holds at most `cap` entries; when it's full, the
+// half that were least recently used are thrown out.
+pub struct Memo<K, V> {
+    map: HashMap<K, (V, u64)>,
+    cap: usize,
+    clock: u64,
+    stats: Stats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evicted: u64,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Memo { map: HashMap::new(), cap, clock: 0, stats: Stats::default() }
+    }
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+    // Forgets every entry, but not the stats.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+    pub fn get(&mut self, k: &K) -> Option<&V> {
+        let now = self.tick();
+        match self.map.get_mut(k) {
+            Some(entry) => {
+                self.stats.hits += 1;
+                entry.1 = now;
+                Some(&entry.0)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+    pub fn insert(&mut self, k: K, v: V) {
+        if self.cap == 0 {
+            return;
+        }
+        if self.map.len() >= self.cap && !self.map.contains_key(&k) {
+            self.evict();
+        }
+        let now = self.tick();
+        self.map.insert(k, (v, now));
+    }
+    fn evict(&mut self) {
+        let mut stamps: Vec<u64> = self.map.values().map(|&(_, t)| t).collect();
+        // Stamps are all different, so this drops exactly the oldest half
+        // (rounding up, so there's always room afterwards).
+        let mid = (stamps.len() - 1) / 2;
+        let (_, &mut cutoff, _) = stamps.select_nth_unstable(mid);
+        let before = self.map.len();
+        self.map.retain(|_, &mut (_, t)| t > cutoff);
+        self.stats.evicted += (before - self.map.len()) as u64;
+    }
+}
+
+// For depth-limited searches (including each round of iterative
+// deepening): the entry is how far below the state has been searched,
+// with `None` meaning all the way.
+impl<K: Hash + Eq + Clone> Memo<K, Option<usize>> {
+    // True if `k` has already been searched at least as far as `limit`
+    // allows, in which case there's no need to do it again.  Otherwise
+    // it's noted as searched that far, on the assumption the caller is
+    // about to.
+    pub fn covered(&mut self, k: &K, limit: Option<usize>) -> bool {
+        let done = match self.get(k) {
+            Some(&None) => true,
+            Some(&Some(was)) => limit.is_some_and(|limit| was >= limit),
+            None => false,
+        };
+        if !done {
+            self.insert(k.clone(), limit);
+        }
+        done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Memo, Stats};
+
+    #[test]
+    fn basics() {
+        let mut m = Memo::new(10);
+        assert!(m.is_empty());
+        assert_eq!(m.get(&"a"), None);
+        m.insert("a", 1);
+        m.insert("b", 2);
+        m.insert("a", 3);
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.get(&"a"), Some(&3));
+        assert_eq!(m.get(&"c"), None);
+        assert_eq!(m.stats(), Stats { hits: 1, misses: 2, evicted: 0 });
+        m.clear();
+        assert_eq!(m.get(&"a"), None);
+        assert_eq!(m.stats().misses, 3);
+    }
+
+    #[test]
+    fn eviction() {
+        let mut m = Memo::new(4);
+        for i in 0..4 {
+            m.insert(i, i * 10);
+        }
+        // Using 0 and 1 makes 2 and 3 the ones to go.
+        assert_eq!(m.get(&1), Some(&10));
+        assert_eq!(m.get(&0), Some(&0));
+        m.insert(4, 40);
+        assert_eq!(m.stats().evicted, 2);
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get(&2), None);
+        assert_eq!(m.get(&3), None);
+        assert_eq!(m.get(&0), Some(&0));
+        assert_eq!(m.get(&4), Some(&40));
+        // Replacing something doesn't make room.
+        m.insert(4, 44);
+        m.insert(5, 50);
+        assert_eq!(m.len(), 4);
+        assert_eq!(m.stats().evicted, 2);
+    }
+
+    #[test]
+    fn no_room() {
+        let mut m = Memo::new(0);
+        m.insert(1, 1);
+        assert_eq!(m.get(&1), None);
+        let mut m = Memo::new(1);
+        m.insert(1, 1);
+        m.insert(2, 2);
+        assert_eq!(m.get(&1), None);
+        assert_eq!(m.get(&2), Some(&2));
+    }
+
+    #[test]
+    fn depths() {
+        let mut m = Memo::new(10);
+        assert!(!m.covered(&'x', Some(3)));
+        assert!(m.covered(&'x', Some(3)));
+        assert!(m.covered(&'x', Some(2)));
+        assert!(!m.covered(&'x', Some(5)));
+        assert!(m.covered(&'x', Some(4)));
+        assert!(!m.covered(&'x', None));
+        assert!(m.covered(&'x', Some(100)));
+        assert!(m.covered(&'x', None));
+        assert!(!m.covered(&'y', None));
+        assert_eq!(m.stats(), Stats { hits: 7, misses: 2, evicted: 0 });
+    }
+}
@@ -0,0 +1,197 @@
+use std::convert::TryFrom;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use best::{Best, Cmp, Score};
+use stack_set::BitSet;
+use walk::Walk;
+
+// Branch and bound across threads.  The search is cut into pieces up
+// front (`work`, usually the first few levels of the tree), and `f`
+// searches under one piece, offering what it finds to a `Best`.
+//
+// Each worker thread has its own `Best`, kept from one piece to the
+// next so it can prune with what it's already found, and pieces are
+// handed out in order, so a worker only ever sees them in increasing
+// order.  At the end the workers' winners are merged by which piece
+// they came from, so ties go to the earliest piece, just as they would
+// if `f` had been run on each piece in turn on one thread: the answer
+// doesn't depend on the number of threads or how they were scheduled.
+//
+// `start` is where the search begins; it can already hold something
+// (from a quicker, shallower search, say) to prune with.
+pub fn search<W, S, T, C, F>(work: &[W], start: Best<S, T, C>, f: F) -> Best<S, T, C>
+    where W: Sync,
+          S: Score + Send + Sync,
+          T: Clone + Send + Sync,
+          C: Cmp<S> + Clone + Send + Sync,
+          F: Fn(&W, &mut Best<S, T, C>) + Sync {
+    search_on(threads(), work, start, f)
+}
+
+// How many threads `search` uses: one per CPU.
+pub fn threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+pub fn search_on<W, S, T, C, F>(threads: usize, work: &[W], mut start: Best<S, T, C>, f: F)
+                                -> Best<S, T, C>
+    where W: Sync,
+          S: Score + Send + Sync,
+          T: Clone + Send + Sync,
+          C: Cmp<S> + Clone + Send + Sync,
+          F: Fn(&W, &mut Best<S, T, C>) + Sync {
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut best = start.fork();
+        // Which piece the current best came from; `None` if it's the
+        // one from `start`.
+        let mut from = None;
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            if i >= work.len() {
+                return (from, best);
+            }
+            let accepted = best.counts().accepted;
+            f(&work[i], &mut best);
+            if best.counts().accepted != accepted {
+                from = Some(i);
+            }
+        }
+    };
+    let threads = threads.max(1).min(work.len());
+    let mut results: Vec<_> = if threads <= 1 {
+        vec![worker()]
+    } else {
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|_| scope.spawn(worker)).collect();
+            handles.into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        })
+    };
+    results.sort_by_key(|&(from, _)| from);
+    for (_, best) in results {
+        start.merge(best);
+    }
+    start
+}
+
+// Pieces for searching the permutations of `0..n` with `Walk`: every
+// path `depth` long (or all of them, if `n` is smaller) that `keep`
+// allows, in the order `Walk` would reach them.  Each one's subtree is
+// then `Walk::within` it.
+pub fn prefixes<B, P>(n: usize, depth: usize, mut keep: P) -> Vec<Vec<B::Idx>>
+    where B: BitSet,
+          B::Idx: TryFrom<usize> + Into<usize>,
+          P: FnMut(&[B::Idx]) -> bool {
+    let depth = depth.min(n);
+    let mut walk = match Walk::<B, _>::new(n, &mut keep) {
+        Some(walk) => walk,
+        None => return vec![],
+    };
+    let mut found = vec![];
+    while let Some(path) = walk.next() {
+        if path.len() == depth {
+            found.push(path);
+            walk.prune();
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use best::{Best, Smallest, Largest};
+    use walk::Walk;
+    use super::{prefixes, search_on};
+
+    type Bits = Vec<u64>;
+
+    // A stand-in for something like day09: the "cost" of a permutation
+    // is how far apart its neighbours are, under some scrambling that
+    // makes for lots of ties.
+    fn cost(path: &[usize]) -> u32 {
+        path.windows(2).map(|w| ((w[0] * 7 + w[1] * 3) % 5) as u32).sum()
+    }
+
+    fn serial(n: usize) -> (u32, Vec<usize>) {
+        let mut best = Best::new(Smallest);
+        for path in Walk::<Bits, _>::new(n, |_: &[usize]| true).unwrap() {
+            if path.len() == n {
+                best.add(cost(&path), &path);
+            }
+        }
+        best.unwrap()
+    }
+
+    fn parallel(threads: usize, n: usize, depth: usize) -> (u32, Vec<usize>) {
+        let work = prefixes::<Bits, _>(n, depth, |_| true);
+        search_on(threads, &work, Best::new(Smallest), |prefix, best| {
+            if prefix.len() == n {
+                best.add(cost(prefix), prefix);
+            }
+            for path in Walk::<Bits, _>::within(n, prefix, |_: &[usize]| true).unwrap() {
+                if path.len() == n {
+                    best.add(cost(&path), &path);
+                }
+            }
+        }).unwrap()
+    }
+
+    #[test]
+    fn pieces() {
+        assert_eq!(prefixes::<Bits, _>(3, 2, |_| true),
+                   vec![vec![0, 1], vec![0, 2], vec![1, 0], vec![1, 2], vec![2, 0], vec![2, 1]]);
+        assert_eq!(prefixes::<Bits, _>(3, 1, |p| p[0] != 1), vec![vec![0], vec![2]]);
+        assert_eq!(prefixes::<Bits, _>(2, 5, |_| true), vec![vec![0, 1], vec![1, 0]]);
+        assert!(prefixes::<Bits, _>(0, 2, |_| true).is_empty());
+    }
+
+    #[test]
+    fn same_as_serial() {
+        for n in 1..7 {
+            let expected = serial(n);
+            for &threads in &[1, 2, 3, 8] {
+                for depth in 1..4 {
+                    assert_eq!(parallel(threads, n, depth), expected,
+                               "n={} threads={} depth={}", n, threads, depth);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_start() {
+        let work: Vec<u32> = (0..100).collect();
+        let mut start = Best::new(Largest);
+        start.add(90, &"start");
+        let best = search_on(4, &work, start.fork(), |&i, best| {
+            best.add(i % 91, &"piece");
+        });
+        assert_eq!(best.get(), Some((90, &"start")));
+        let best = search_on(4, &work, start, |&i, best| {
+            if best.can_win(i) {
+                best.add(i, &"piece");
+            }
+        });
+        assert_eq!(best.get(), Some((99, &"piece")));
+    }
+
+    #[test]
+    fn nothing_to_do() {
+        let work: Vec<u32> = vec![];
+        let best = search_on(4, &work, Best::<u32, u32, _>::new(Smallest), |_, _| panic!());
+        assert_eq!(best.finish(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "piece 13")]
+    fn panics() {
+        let work: Vec<u32> = (0..20).collect();
+        search_on(4, &work, Best::<u32, u32, _>::new(Smallest), |&i, _| {
+            assert!(i != 13, "piece {}", i);
+        });
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use input::{Line, LineError};
+
+// A cursor over one line of input, for writing little recursive-descent
+// parsers: each method skips any whitespace, then takes one thing off
+// the front or fails with an error pointing at where it went wrong.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    num: usize,
+    text: &'a str,
+    pos: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn quoted_list(lits: &[&str]) -> String {
+    let quoted: Vec<_> = lits.iter().map(|lit| format!("{:?}", lit)).collect();
+    match quoted.split_last() {
+        None => "nothing".to_owned(),
+        Some((last, [])) => last.clone(),
+        Some((last, init)) => format!("one of {} or {}", init.join(", "), last),
+    }
+}
+
+// Runs `f` over the whole of one line (numbered `num`), which has to use
+// all of it.
+pub fn scan<'a, T, F>(num: usize, text: &'a str, f: F) -> Result<T, LineError>
+    where F: FnOnce(&mut Scanner<'a>) -> Result<T, LineError> {
+    let mut s = Scanner::new(num, text);
+    let t = f(&mut s)?;
+    s.end()?;
+    Ok(t)
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(num: usize, text: &'a str) -> Self {
+        Scanner { num, text, pos: 0 }
+    }
+    pub fn of(line: &'a Line) -> Self {
+        Scanner::new(line.num, &line.text)
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    fn column(&self, pos: usize) -> usize {
+        self.text[..pos].chars().count() + 1
+    }
+    // Whatever's next, for error messages.
+    fn found(&self) -> String {
+        match self.rest().split(char::is_whitespace).next() {
+            Some(tok) if !tok.is_empty() => format!("{:?}", tok),
+            _ => "end of line".to_owned(),
+        }
+    }
+    fn expected(&mut self, what: &str) -> LineError {
+        self.skip_ws();
+        if self.at_end() {
+            self.error(format!("unexpected end of line; expected {}", what))
+        } else {
+            self.error(format!("expected {}, found {}", what, self.found()))
+        }
+    }
+
+    // What's left, not counting whitespace at either end.
+    pub fn rest(&self) -> &'a str {
+        self.text[self.pos..].trim()
+    }
+    pub fn at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+    pub fn error<M: Into<String>>(&self, message: M) -> LineError {
+        let rest = &self.text[self.pos..];
+        let pos = self.pos + rest.len() - rest.trim_start().len();
+        LineError::at(self.num, self.column(pos), message)
+    }
+    // `sub` has to be something this scanner returned earlier.
+    pub fn error_at<M: Into<String>>(&self, sub: &str, message: M) -> LineError {
+        let base = self.text.as_ptr() as usize;
+        let ptr = sub.as_ptr() as usize;
+        assert!(ptr >= base && ptr + sub.len() <= base + self.text.len(),
+                "error_at: {:?} isn't part of line {}", sub, self.num);
+        LineError::at(self.num, self.column(ptr - base), message)
+    }
+
+    // One or more chars matching `pred`; `what` is for the error.
+    pub fn take_while<P>(&mut self, what: &str, mut pred: P) -> Result<&'a str, LineError>
+        where P: FnMut(char) -> bool {
+        self.skip_ws();
+        let rest = &self.text[self.pos..];
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.expected(what));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+    // Letters, digits and underscores.
+    pub fn word(&mut self) -> Result<&'a str, LineError> {
+        self.take_while("a word", is_word_char)
+    }
+    // Anything up to the next whitespace.
+    pub fn token(&mut self) -> Result<&'a str, LineError> {
+        self.take_while("something", |c| !c.is_whitespace())
+    }
+
+    // Where `lit` would end if it were next.  Whitespace in `lit` matches
+    // any amount of whitespace, and a literal ending in a letter or digit
+    // can't be followed by another one ("gain" doesn't match "gains").
+    fn match_lit(&self, lit: &str) -> Option<usize> {
+        let mut pos = self.pos;
+        for word in lit.split_whitespace() {
+            let rest = &self.text[pos..];
+            let rest = rest.trim_start();
+            if !rest.starts_with(word) {
+                return None;
+            }
+            let after = &rest[word.len()..];
+            if word.ends_with(is_word_char) && after.starts_with(is_word_char) {
+                return None;
+            }
+            pos = self.text.len() - after.len();
+        }
+        Some(pos)
+    }
+    // Takes `lit` if it's next.
+    pub fn opt(&mut self, lit: &str) -> bool {
+        match self.match_lit(lit) {
+            Some(end) => { self.pos = end; true }
+            None => false,
+        }
+    }
+    pub fn expect(&mut self, lit: &str) -> Result<(), LineError> {
+        if self.opt(lit) { Ok(()) } else { Err(self.expected(&format!("{:?}", lit))) }
+    }
+    // Whichever of the literals is next (the first, if more than one).
+    pub fn one_of<T: Copy>(&mut self, choices: &[(&str, T)]) -> Result<T, LineError> {
+        for &(lit, val) in choices {
+            if self.opt(lit) {
+                return Ok(val);
+            }
+        }
+        let lits: Vec<_> = choices.iter().map(|&(lit, _)| lit).collect();
+        Err(self.expected(&quoted_list(&lits)))
+    }
+
+    // An optional sign and then everything up to the next non-word char
+    // goes to `from_str`, so "12ab" is a bad number and not 12 then "ab".
+    pub fn int<T>(&mut self) -> Result<T, LineError> where T: FromStr, T::Err: Display {
+        self.skip_ws();
+        let start = self.pos;
+        let rest = &self.text[start..];
+        let sign = if rest.starts_with(['+', '-']) { 1 } else { 0 };
+        let len = rest[sign..].find(|c| !is_word_char(c)).unwrap_or(rest.len() - sign) + sign;
+        let digits = &rest[..len];
+        let n = T::from_str(digits).map_err(|e| {
+            LineError::at(self.num, self.column(start), format!("bad number {:?}: {}", digits, e))
+        })?;
+        self.pos += len;
+        Ok(n)
+    }
+    pub fn int_in<T>(&mut self, range: RangeInclusive<T>) -> Result<T, LineError>
+        where T: FromStr + PartialOrd + Display, T::Err: Display {
+        let before = self.clone();
+        let n = self.int()?;
+        if !range.contains(&n) {
+            return Err(before.error(format!("{} is out of range {}..={}",
+                                            n, range.start(), range.end())));
+        }
+        Ok(n)
+    }
+
+    // Two things like "12,34", with no whitespace anywhere.
+    pub fn comma_pair<T, F>(&mut self, mut f: F) -> Result<(T, T), LineError>
+        where F: FnMut(&mut Scanner<'a>) -> Result<T, LineError> {
+        let tok = self.token()?;
+        let end = self.pos;
+        let mut sub = Scanner { num: self.num, text: &self.text[..end], pos: end - tok.len() };
+        let bad = || self.error_at(tok, format!("expected comma-separated pair; got {:?}", tok));
+        let x = f(&mut sub)?;
+        if !sub.text[sub.pos..].starts_with(',') {
+            return Err(bad());
+        }
+        sub.pos += 1;
+        let y = f(&mut sub)?;
+        if sub.pos != end {
+            return Err(bad());
+        }
+        Ok((x, y))
+    }
+    // One or more things with `sep` between them.
+    pub fn sep_by<T, F>(&mut self, sep: &str, mut f: F) -> Result<Vec<T>, LineError>
+        where F: FnMut(&mut Scanner<'a>) -> Result<T, LineError> {
+        let mut items = vec![f(self)?];
+        while self.opt(sep) {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    pub fn end(&mut self) -> Result<(), LineError> {
+        if self.at_end() {
+            self.pos = self.text.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected text {:?} at end of line", self.rest())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use input::LineError;
+    use super::{scan, Scanner};
+
+    fn err<T: ::std::fmt::Debug>(r: Result<T, LineError>) -> String {
+        r.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn words() {
+        let mut s = Scanner::new(3, "  Alice would\tgain 54.");
+        assert_eq!(s.word(), Ok("Alice"));
+        s.expect("would").unwrap();
+        assert_eq!(s.one_of(&[("gain", 1), ("lose", -1)]), Ok(1));
+        assert_eq!(s.int::<i32>(), Ok(54));
+        assert_eq!(s.rest(), ".");
+        assert!(!s.at_end());
+        assert_eq!(s.token(), Ok("."));
+        assert!(s.at_end());
+        s.end().unwrap();
+    }
+
+    #[test]
+    fn literals() {
+        let mut s = Scanner::new(1, "can  fly 14 km/s for 10 seconds, but");
+        s.expect("can fly").unwrap();
+        assert!(!s.opt("14 km/h"));
+        assert_eq!(s.int::<u8>(), Ok(14));
+        s.expect("km/s for").unwrap();
+        s.int::<u8>().unwrap();
+        assert!(!s.opt("second"));
+        assert_eq!(s.one_of(&[("second", 1), ("seconds", 2)]), Ok(2));
+        assert!(s.opt(","));
+        assert_eq!(err(s.expect("and")), "line 1, column 34: expected \"and\", found \"but\"");
+    }
+
+    #[test]
+    fn ints() {
+        assert_eq!(scan(1, "+23", |s| s.int::<i8>()), Ok(23));
+        assert_eq!(scan(1, "-23", |s| s.int::<i8>()), Ok(-23));
+        assert_eq!(err(scan(1, "x 12ab", |s| { s.word()?; s.int::<u32>() })),
+                   "line 1, column 3: bad number \"12ab\": invalid digit found in string");
+        assert_eq!(err(scan(1, "-1", |s| s.int::<u32>())),
+                   "line 1, column 1: bad number \"-1\": invalid digit found in string");
+        assert_eq!(err(scan(1, "300", |s| s.int::<u8>())),
+                   "line 1, column 1: bad number \"300\": number too large to fit in target type");
+        assert_eq!(scan(1, "15", |s| s.int_in(0..=15u8)), Ok(15));
+        assert_eq!(err(scan(1, " 16", |s| s.int_in(0..=15u8))),
+                   "line 1, column 2: 16 is out of range 0..=15");
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(scan(1, "499,500", |s| s.comma_pair(|s| s.int::<u16>())), Ok((499, 500)));
+        for &bad in &["499", "499,500,", "499;500"] {
+            let msg = err(scan(1, bad, |s| s.comma_pair(|s| s.int::<u16>())));
+            assert!(msg.contains("expected comma-separated pair"), "{}: {}", bad, msg);
+        }
+        assert_eq!(err(scan(1, "1,", |s| s.comma_pair(|s| s.int::<u16>()))),
+                   "line 1, column 3: bad number \"\": cannot parse integer from empty string");
+    }
+
+    #[test]
+    fn lists() {
+        let fact = |s: &mut Scanner<'static>| -> Result<(&'static str, u8), LineError> {
+            let k = s.word()?;
+            s.expect(":")?;
+            Ok((k, s.int()?))
+        };
+        assert_eq!(scan(1, "cars: 10, trees : 0", |s| s.sep_by(",", fact)),
+                   Ok(vec![("cars", 10), ("trees", 0)]));
+        assert_eq!(err(scan(1, "cars: 10,", |s| s.sep_by(",", fact))),
+                   "line 1, column 10: unexpected end of line; expected a word");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(err(scan(7, "turn on", |s| s.one_of(&[("toggle", 0), ("turn off", 1)]))),
+                   "line 7, column 1: expected one of \"toggle\" or \"turn off\", found \"turn\"");
+        assert_eq!(err(scan(1, "a b c", |s| s.word())),
+                   "line 1, column 3: unexpected text \"b c\" at end of line");
+        let mut s = Scanner::new(2, "héllo wörld");
+        s.word().unwrap();
+        let w = s.word().unwrap();
+        assert_eq!(s.error_at(w, "no"), LineError::at(2, 7, "no"));
+    }
+}
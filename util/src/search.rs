@@ -0,0 +1,395 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use best::{self, Counts, Smallest};
+use memo::Memo;
+use par;
+
+// Something to search: states, the steps between them and what each
+// costs, and which states are goals.  The solvers below all find the
+// cheapest way from `start` to a goal, and what the steps were.
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+    type Step: Clone;
+    // `Default` is zero.
+    type Cost: Copy + Ord + Add<Output = Self::Cost> + Default;
+
+    fn start(&self) -> Self::State;
+    // Everywhere `st` leads, in the order to try them, with the step
+    // taken and its cost.
+    fn successors(&self, st: &Self::State) -> Vec<(Self::Step, Self::State, Self::Cost)>;
+    fn is_goal(&self, st: &Self::State) -> bool;
+    // A guess at the cost from `st` to the nearest goal, for `astar` and
+    // for pruning in `iddfs`.  It has to be optimistic (never more than
+    // the truth) or the answer might not be the cheapest.
+    fn heuristic(&self, _st: &Self::State) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+type FoundFor<P> = Found<<P as Problem>::Step, <P as Problem>::Cost>;
+
+// Every state reached, with how: the state it came from (by index) and
+// the step from there.
+struct Node<S, St, C> {
+    st: St,
+    from: Option<(usize, S)>,
+    cost: C,
+}
+struct Tree<S, St, C> {
+    nodes: Vec<Node<S, St, C>>,
+}
+impl<S: Clone, St, C: Copy> Tree<S, St, C> {
+    fn new(start: St, zero: C) -> Self {
+        Tree { nodes: vec![Node { st: start, from: None, cost: zero }] }
+    }
+    fn add(&mut self, st: St, from: usize, step: S, cost: C) -> usize {
+        self.nodes.push(Node { st, from: Some((from, step)), cost });
+        self.nodes.len() - 1
+    }
+    fn found(&self, mut i: usize) -> Found<S, C> {
+        let cost = self.nodes[i].cost;
+        let mut path = vec![];
+        while let Some((from, ref step)) = self.nodes[i].from {
+            path.push(step.clone());
+            i = from;
+        }
+        path.reverse();
+        Found { cost, path }
+    }
+}
+
+// Breadth first: the goal with the fewest steps (ignoring what they
+// cost, except to add it up), or `None` if there isn't one.  Every state
+// reached is remembered, so there had better not be too many.
+pub fn bfs<P: Problem>(p: &P) -> Option<FoundFor<P>> {
+    let mut tree = Tree::new(p.start(), P::Cost::default());
+    if p.is_goal(&tree.nodes[0].st) {
+        return Some(tree.found(0));
+    }
+    let mut seen = HashMap::new();
+    seen.insert(tree.nodes[0].st.clone(), ());
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+    while let Some(i) = queue.pop_front() {
+        let g = tree.nodes[i].cost;
+        for (step, next, c) in p.successors(&tree.nodes[i].st) {
+            if let Entry::Vacant(e) = seen.entry(next.clone()) {
+                e.insert(());
+                let goal = p.is_goal(&next);
+                let j = tree.add(next, i, step, g + c);
+                if goal {
+                    return Some(tree.found(j));
+                }
+                queue.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<P: Problem>(p: &P) -> Option<FoundFor<P>> {
+    best_first(p, false)
+}
+
+pub fn astar<P: Problem>(p: &P) -> Option<FoundFor<P>> {
+    best_first(p, true)
+}
+
+// States are taken cheapest first (counting the heuristic, if wanted),
+// with ties going to whichever was reached first.  A state reached again
+// more cheaply is searched again, so the heuristic only needs to be
+// optimistic and not also consistent.
+fn best_first<P: Problem>(p: &P, guess: bool) -> Option<FoundFor<P>> {
+    let h = |st: &P::State| if guess { p.heuristic(st) } else { P::Cost::default() };
+    let start = p.start();
+    let mut cheapest = HashMap::new();
+    cheapest.insert(start.clone(), P::Cost::default());
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((h(&start), 0)));
+    let mut tree = Tree::new(start, P::Cost::default());
+    while let Some(Reverse((_, i))) = heap.pop() {
+        let g = tree.nodes[i].cost;
+        if cheapest[&tree.nodes[i].st] < g {
+            continue;
+        }
+        if p.is_goal(&tree.nodes[i].st) {
+            return Some(tree.found(i));
+        }
+        for (step, next, c) in p.successors(&tree.nodes[i].st) {
+            let ng = g + c;
+            match cheapest.entry(next.clone()) {
+                Entry::Occupied(ref e) if *e.get() <= ng => continue,
+                Entry::Occupied(mut e) => { e.insert(ng); }
+                Entry::Vacant(e) => { e.insert(ng); }
+            }
+            let f = ng + h(&next);
+            let j = tree.add(next, i, step, ng);
+            heap.push(Reverse((f, j)));
+        }
+    }
+    None
+}
+
+// How many states each thread of `iddfs` remembers.
+const SEEN_CAP: usize = 1 << 20;
+
+type BestFor<P> = best::Best<<P as Problem>::Cost, Vec<<P as Problem>::Step>, Smallest>;
+
+struct Dfs<'a, P: Problem + 'a> {
+    p: &'a P,
+    path: Vec<P::Step>,
+    // Each state's cost, and how many steps below it were searched.
+    seen: Memo<P::State, (P::Cost, Option<usize>)>,
+    cut: &'a AtomicBool,
+}
+impl<'a, P: Problem> Dfs<'a, P> {
+    fn covered(&mut self, st: &P::State, g: P::Cost, limit: Option<usize>) -> bool {
+        let done = match self.seen.get(st) {
+            Some(&(was_g, was_limit)) => was_g <= g && match (was_limit, limit) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(was), Some(now)) => was >= now,
+            },
+            None => false,
+        };
+        if !done {
+            self.seen.insert(st.clone(), (g, limit));
+        }
+        done
+    }
+    fn search(&mut self, st: &P::State, g: P::Cost, limit: Option<usize>, b: &mut BestFor<P>) {
+        if self.p.is_goal(st) {
+            b.add(g, &self.path[..]);
+            return;
+        }
+        if limit == Some(0) {
+            self.cut.store(true, Ordering::Relaxed);
+            return;
+        }
+        if !b.can_win(g + self.p.heuristic(st)) {
+            return;
+        }
+        // Having been here before no more cheaply, and searched at least
+        // as deep, there's nothing new to find.
+        if self.covered(st, g, limit) {
+            return;
+        }
+        for (step, next, c) in self.p.successors(st) {
+            self.path.push(step);
+            self.search(&next, g + c, limit.map(|n| n - 1), b);
+            self.path.pop();
+        }
+    }
+}
+
+// Iterative deepening: depth-first branch and bound, remembering only
+// the current path (and a bounded `Memo` of states already searched),
+// first limited to `first` steps, then `step` more each time, until a
+// goal is found.  Then, unless nothing was cut off by the limit, once
+// more with no limit (but pruned by what's been found) in case a longer
+// way is cheaper; that last search has to end, so costs had better be
+// positive or the states finite.  Returns `None` if the search runs out
+// of states without finding a goal.
+//
+// Ties go to the first goal found, with successors searched in order;
+// each of the start's successors is its own piece for `par::search`.
+pub fn iddfs<P>(p: &P, first: usize, step: usize) -> Option<FoundFor<P>>
+    where P: Problem + Sync,
+          P::State: Send + Sync,
+          P::Step: Send + Sync,
+          P::Cost: Send + Sync {
+    iddfs_counted(p, first, step).0
+}
+
+// The same, and how the branch and bound went, over every pass.
+pub fn iddfs_counted<P>(p: &P, first: usize, step: usize) -> (Option<FoundFor<P>>, Counts)
+    where P: Problem + Sync,
+          P::State: Send + Sync,
+          P::Step: Send + Sync,
+          P::Cost: Send + Sync {
+    let start = p.start();
+    if p.is_goal(&start) {
+        return (Some(Found { cost: P::Cost::default(), path: vec![] }), Counts::default());
+    }
+    let firsts = p.successors(&start);
+    let cut = AtomicBool::new(false);
+    let pass = |b: BestFor<P>, limit: Option<usize>| {
+        cut.store(false, Ordering::Relaxed);
+        let b = par::search(&firsts, b, |&(ref step, ref st, c), b| {
+            if limit == Some(0) {
+                cut.store(true, Ordering::Relaxed);
+                return;
+            }
+            let mut dfs = Dfs { p, path: vec![step.clone()], seen: Memo::new(SEEN_CAP), cut: &cut };
+            dfs.search(st, c, limit.map(|n| n - 1), b);
+        });
+        (b, cut.load(Ordering::Relaxed))
+    };
+    let mut b = BestFor::<P>::new(Smallest);
+    let mut depth = first;
+    loop {
+        let (nb, cut) = pass(b, Some(depth));
+        b = nb;
+        if b.get().is_some() {
+            if cut {
+                b = pass(b, None).0;
+            }
+            break;
+        }
+        if !cut {
+            break;
+        }
+        depth += step;
+    }
+    let counts = b.counts();
+    (b.finish().map(|(cost, path)| Found { cost, path }), counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use best::Counts;
+    use super::{astar, bfs, dijkstra, iddfs, iddfs_counted, Found, Problem};
+
+    // Getting around a little map, where '#' is a wall, digits are that
+    // much harder going than '.', and 'G's are goals.
+    struct Map(Vec<&'static [u8]>);
+    impl Map {
+        fn goals(&self) -> Vec<(usize, usize)> {
+            let mut goals = vec![];
+            for (y, row) in self.0.iter().enumerate() {
+                for (x, &c) in row.iter().enumerate() {
+                    if c == b'G' {
+                        goals.push((x, y));
+                    }
+                }
+            }
+            goals
+        }
+    }
+    impl Problem for Map {
+        type State = (usize, usize);
+        type Step = char;
+        type Cost = u32;
+        fn start(&self) -> (usize, usize) {
+            (0, 0)
+        }
+        fn successors(&self, &(x, y): &(usize, usize)) -> Vec<(char, (usize, usize), u32)> {
+            let mut next = vec![];
+            let moves = [('E', x + 1, y), ('S', x, y + 1), ('W', x.wrapping_sub(1), y),
+                         ('N', x, y.wrapping_sub(1))];
+            for &(dir, x, y) in &moves {
+                let c = match self.0.get(y).and_then(|row| row.get(x)) {
+                    Some(&c) => c,
+                    None => continue,
+                };
+                match c {
+                    b'#' => (),
+                    b'1'..=b'9' => next.push((dir, (x, y), (c - b'0') as u32)),
+                    _ => next.push((dir, (x, y), 1)),
+                }
+            }
+            next
+        }
+        fn is_goal(&self, &(x, y): &(usize, usize)) -> bool {
+            self.0[y][x] == b'G'
+        }
+        fn heuristic(&self, &(x, y): &(usize, usize)) -> u32 {
+            self.goals().iter().map(|&(gx, gy)| {
+                ((gx as isize - x as isize).abs() + (gy as isize - y as isize).abs()) as u32
+            }).min().unwrap_or(0)
+        }
+    }
+
+    fn found(cost: u32, path: &str) -> Option<Found<char, u32>> {
+        Some(Found { cost, path: path.chars().collect() })
+    }
+
+    #[test]
+    fn detour() {
+        let map = Map(vec![b".9G",
+                           b"...",
+                           b"#.."]);
+        assert_eq!(bfs(&map), found(10, "EE"));
+        assert_eq!(dijkstra(&map), found(4, "SEEN"));
+        assert_eq!(astar(&map), found(4, "SEEN"));
+        assert_eq!(iddfs(&map, 1, 1), found(4, "SEEN"));
+        assert_eq!(iddfs(&map, 10, 1), found(4, "SEEN"));
+    }
+
+    #[test]
+    fn ties() {
+        let map = Map(vec![b"...",
+                           b"..G"]);
+        for &f in &[bfs, dijkstra, astar] {
+            assert_eq!(f(&map), found(3, "EES"));
+        }
+        assert_eq!(iddfs(&map, 2, 1), found(3, "EES"));
+    }
+
+    #[test]
+    fn nearest() {
+        let map = Map(vec![b"..5G",
+                           b".#..",
+                           b"G#.."]);
+        for &f in &[bfs, dijkstra, astar] {
+            assert_eq!(f(&map), found(2, "SS"));
+        }
+        assert_eq!(iddfs(&map, 1, 1), found(2, "SS"));
+    }
+
+    #[test]
+    fn nowhere() {
+        let map = Map(vec![b"G.#..",
+                           b"###.G"]);
+        assert_eq!(bfs(&map), found(0, ""));
+        assert_eq!(iddfs(&map, 1, 1), found(0, ""));
+        assert_eq!(iddfs_counted(&map, 1, 1).1, Counts::default());
+        let map = Map(vec![b"..#..",
+                           b"###.G"]);
+        for &f in &[bfs, dijkstra, astar] {
+            assert_eq!(f(&map), None);
+        }
+        assert_eq!(iddfs(&map, 1, 1), None);
+    }
+
+    // Lots of ways to the same places; the cheapest is along the top and
+    // then straight down.
+    #[test]
+    fn bigger() {
+        let mut rows = vec![];
+        for y in 0..30 {
+            let mut row = vec![b'2'; 30];
+            if y == 0 {
+                row = vec![b'1'; 30];
+            }
+            row[29] = b'1';
+            if y == 29 {
+                row[29] = b'G';
+            }
+            rows.push(&*Box::leak(row.into_boxed_slice()));
+        }
+        let map = Map(rows);
+        let expected: String = "E".repeat(29) + &"S".repeat(29);
+        for &f in &[dijkstra, astar] {
+            assert_eq!(f(&map), found(58, &expected));
+        }
+        assert_eq!(iddfs(&map, 58, 10), found(58, &expected));
+        // Everything that got as far as the goal was offered, and the
+        // rest of the map is there to be pruned.
+        let (result, counts) = iddfs_counted(&map, 58, 10);
+        assert_eq!(result, found(58, &expected));
+        assert!(counts.accepted >= 1);
+        assert!(counts.pruned > 0);
+    }
+}
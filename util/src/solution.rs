@@ -0,0 +1,144 @@
+use std::env;
+use std::error::Error;
+use std::process;
+use input::Input;
+use json::Json;
+
+// What the `aoc` runner needs from each day: both parts of the puzzle,
+// each given the whole puzzle input and returning the answer as text.
+pub type Answer = Result<String, Box<dyn Error>>;
+
+// An answer along with whatever backs it up (the route, the spells
+// cast...), for `--json` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub answer: String,
+    pub witness: Option<Json>,
+}
+impl Report {
+    pub fn new(answer: String) -> Self {
+        Report { answer, witness: None }
+    }
+    pub fn with_witness<J: Into<Json>>(answer: String, witness: J) -> Self {
+        Report { answer, witness: Some(witness.into()) }
+    }
+}
+
+pub trait Solution {
+    fn part1(&self, input: &str) -> Answer;
+    fn part2(&self, input: &str) -> Answer;
+
+    fn part(&self, n: u32, input: &str) -> Answer {
+        match n {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(format!("there is no part {}", n).into()),
+        }
+    }
+    // Days with something to show for their answers override this.
+    fn report(&self, n: u32, input: &str) -> Result<Report, Box<dyn Error>> {
+        self.part(n, input).map(Report::new)
+    }
+}
+
+// One object per run, like
+// `{"day":9,"parts":[{"part":1,"answer":"605","witness":{...}},...]}`;
+// a part that fails has an "error" instead of an answer, and then the
+// flag returned is false.
+pub fn json_report(day: u32, soln: &dyn Solution, parts: &[u32], input: &str) -> (Json, bool) {
+    let mut ok = true;
+    let parts = parts.iter().map(|&n| {
+        let mut fields = vec![("part", Json::from(n))];
+        match soln.report(n, input) {
+            Ok(report) => {
+                fields.push(("answer", Json::from(report.answer)));
+                if let Some(witness) = report.witness {
+                    fields.push(("witness", witness));
+                }
+            }
+            Err(e) => {
+                fields.push(("error", Json::from(e.to_string())));
+                ok = false;
+            }
+        }
+        Json::obj(fields)
+    }).collect::<Vec<_>>();
+    (Json::obj(vec![("day", Json::from(day)), ("parts", Json::Arr(parts))]), ok)
+}
+
+// For the day binaries: `dayN --json [<input file>|-]` prints the JSON
+// report for both parts and exits, instead of the usual prose; without
+// the flag this just returns.
+pub fn json_mode(day: u32, soln: &dyn Solution) {
+    let mut args = env::args().skip(1);
+    if args.next().as_deref() != Some("--json") {
+        return;
+    }
+    let text = Input::from_arg(args.next()).map_err(|e| e.to_string())
+        .and_then(|input| input.read_text().map_err(|e| e.to_string()))
+        .unwrap_or_else(|msg| {
+            eprintln!("can't read input: {}", msg);
+            process::exit(1);
+        });
+    let (json, ok) = json_report(day, soln, &[1, 2], &text);
+    println!("{}", json);
+    process::exit(if ok { 0 } else { 1 });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use json::Json;
+    use super::{json_report, Answer, Report, Solution};
+
+    struct Lengths;
+    impl Solution for Lengths {
+        fn part1(&self, input: &str) -> Answer { Ok(input.len().to_string()) }
+        fn part2(&self, input: &str) -> Answer {
+            input.lines().map(|l| l.len()).max().map(|n| n.to_string()).ok_or("no lines".into())
+        }
+    }
+
+    #[test]
+    fn parts() {
+        assert_eq!(Lengths.part(1, "ab\nc").unwrap(), "4");
+        assert_eq!(Lengths.part(2, "ab\nc").unwrap(), "2");
+        assert_eq!(Lengths.part(2, "").unwrap_err().to_string(), "no lines");
+        assert_eq!(Lengths.part(3, "").unwrap_err().to_string(), "there is no part 3");
+    }
+
+    struct Longest;
+    impl Solution for Longest {
+        fn part1(&self, input: &str) -> Answer { Lengths.part2(input) }
+        fn part2(&self, input: &str) -> Answer { self.report(2, input).map(|r| r.answer) }
+        fn report(&self, n: u32, input: &str) -> Result<Report, Box<dyn Error>> {
+            if n != 2 {
+                return self.part(n, input).map(Report::new);
+            }
+            let line = input.lines().max_by_key(|l| l.len()).ok_or("no lines")?;
+            Ok(Report::with_witness(line.len().to_string(), line))
+        }
+    }
+
+    #[test]
+    fn reports() {
+        assert_eq!(Lengths.report(1, "ab\nc").unwrap(), Report::new("4".to_owned()));
+        assert_eq!(Longest.report(2, "ab\nc").unwrap(),
+                   Report { answer: "2".to_owned(), witness: Some(Json::from("ab")) });
+        assert_eq!(Longest.part2("ab\nc").unwrap(), "2");
+    }
+
+    #[test]
+    fn json() {
+        let (json, ok) = json_report(17, &Longest, &[1, 2], "ab\nc");
+        assert!(ok);
+        assert_eq!(json.to_string(),
+                   "{\"day\":17,\"parts\":[{\"part\":1,\"answer\":\"2\"},\
+                    {\"part\":2,\"answer\":\"2\",\"witness\":\"ab\"}]}");
+        let (json, ok) = json_report(17, &Longest, &[2, 3], "");
+        assert!(!ok);
+        assert_eq!(json.to_string(),
+                   "{\"day\":17,\"parts\":[{\"part\":2,\"error\":\"no lines\"},\
+                    {\"part\":3,\"error\":\"there is no part 3\"}]}");
+    }
+}
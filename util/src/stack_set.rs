@@ -22,13 +22,26 @@ impl<B: BitSet> StackSet<B> {
         })
     }
     pub fn push<F>(&mut self, i: B::Idx, f: F) where F: FnOnce(&mut Self) {
-        if !self.mask.contains(i) {
-            self.stack.push(i);
-            self.mask.set(i);
+        if self.try_push(i) {
             f(self);
-            let _i = self.stack.pop().unwrap();
-            self.mask.clear(i);
+            let _i = self.pop();
+        }
+    }
+    // Like `push` but without the callback; it's up to the caller to
+    // `pop` later.  Returns false (and does nothing) if `i` is already in.
+    pub fn try_push(&mut self, i: B::Idx) -> bool {
+        if self.mask.contains(i) {
+            return false;
         }
+        self.stack.push(i);
+        self.mask.set(i);
+        true
+    }
+    pub fn pop(&mut self) -> Option<B::Idx> {
+        self.stack.pop().map(|i| {
+            self.mask.clear(i);
+            i
+        })
     }
     pub fn is_full(&self) -> bool {
         self.mask.is_full()
@@ -76,9 +89,70 @@ macro_rules! make_impls { { $($ty:ty),* } => {
 }}
 make_impls!{u8, u16, u32, usize, u64}
 
+// For anything bigger than a machine word: an array of them, either
+// on the heap or (if the size is known up front) inline.  As above,
+// bits past the end start out set so that fullness is easy to check.
+fn words_init(words: &mut [u64], n: usize) {
+    for (i, word) in words.iter_mut().enumerate() {
+        let lo = i * 64;
+        *word = if n <= lo { !0 } else if n - lo >= 64 { 0 } else { !0 << (n - lo) };
+    }
+}
+#[inline]
+fn words_contains(words: &[u64], i: usize) -> bool {
+    words[i / 64] & 1 << (i % 64) != 0
+}
+#[inline]
+fn words_set(words: &mut [u64], i: usize) {
+    words[i / 64] |= 1 << (i % 64)
+}
+#[inline]
+fn words_clear(words: &mut [u64], i: usize) {
+    words[i / 64] &= !(1 << (i % 64))
+}
+
+impl BitSet for Vec<u64> {
+    type Idx = usize;
+    fn new(n: usize) -> Option<Self> {
+        let mut words = vec![0; n.div_ceil(64)];
+        words_init(&mut words, n);
+        Some(words)
+    }
+    fn is_full(&self) -> bool {
+        self.iter().all(|&w| !w == 0)
+    }
+    #[inline]
+    fn contains(&self, i: usize) -> bool { words_contains(self, i) }
+    #[inline]
+    fn set(&mut self, i: usize) { words_set(self, i) }
+    #[inline]
+    fn clear(&mut self, i: usize) { words_clear(self, i) }
+}
+
+impl<const N: usize> BitSet for [u64; N] {
+    type Idx = usize;
+    fn new(n: usize) -> Option<Self> {
+        if n > N * 64 {
+            return None;
+        }
+        let mut words = [0; N];
+        words_init(&mut words, n);
+        Some(words)
+    }
+    fn is_full(&self) -> bool {
+        self.iter().all(|&w| !w == 0)
+    }
+    #[inline]
+    fn contains(&self, i: usize) -> bool { words_contains(self, i) }
+    #[inline]
+    fn set(&mut self, i: usize) { words_set(self, i) }
+    #[inline]
+    fn clear(&mut self, i: usize) { words_clear(self, i) }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::StackSet;
+    use super::{StackSet, BitSet};
 
     type S32 = StackSet<u32>;
     
@@ -108,4 +182,60 @@ mod tests {
         s.push(0, |_s| called = true);
         assert!(called);
     }
+
+    #[test]
+    fn too_big() {
+        assert!(S32::new(33).is_none());
+        assert!(StackSet::<[u64; 2]>::new(129).is_none());
+    }
+
+    // Visits every permutation of `n` things; returns how many there were.
+    fn count_perms<B: BitSet<Idx = usize>>(s: &mut StackSet<B>, n: usize) -> usize {
+        if s.is_full() {
+            return 1;
+        }
+        let mut total = 0;
+        for i in 0..n {
+            s.push(i, |s| total += count_perms(s, n));
+        }
+        total
+    }
+
+    #[test]
+    fn wide_perms() {
+        let mut s = StackSet::<Vec<u64>>::new(5).unwrap();
+        assert_eq!(count_perms(&mut s, 5), 120);
+        let mut s = StackSet::<[u64; 1]>::new(5).unwrap();
+        assert_eq!(count_perms(&mut s, 5), 120);
+        let mut s = StackSet::<Vec<u64>>::new(0).unwrap();
+        assert!(s.is_full());
+        assert_eq!(count_perms(&mut s, 0), 1);
+    }
+
+    #[test]
+    fn hundreds() {
+        for &n in &[63, 64, 65, 128, 300] {
+            let mut s = StackSet::<Vec<u64>>::new(n).unwrap();
+            fn fill(s: &mut StackSet<Vec<u64>>, i: usize, n: usize) {
+                if i == n {
+                    assert!(s.is_full());
+                    assert_eq!(s.len(), n);
+                    assert_eq!(s[n - 1], n - 1);
+                    return;
+                }
+                assert!(!s.is_full());
+                s.push(i, |s| fill(s, i + 1, n));
+            }
+            fill(&mut s, 0, n);
+            assert!(!s.is_full());
+            assert_eq!(s.len(), 0);
+        }
+        let mut s = StackSet::<[u64; 5]>::new(300).unwrap();
+        let mut called = false;
+        s.push(299, |s| {
+            s.push(299, |_| panic!("pushed twice"));
+            called = true;
+        });
+        assert!(called);
+    }
 }
@@ -1,84 +1,423 @@
-use std::iter;
-use std::rc::Rc;
-use lazy_iter::LazyIter;
+use std::marker::PhantomData;
 
 pub trait Total<Item = Self>: Sized {
+    fn zero() -> Self;
     fn is_zero(&self) -> bool;
     fn checked_sub(self, other: Item) -> Option<Self>;
+    fn checked_add(self, other: Item) -> Option<Self>;
     // z.is_zero() && !Self::from(p).is_zero() -> z.checked_sub(o) == None
     // (i.e., this is for unsigned types)
 }
 
 macro_rules! impl_total { { $($int:ty),* } => {
     $(impl<T> Total<T> for $int where $int: From<T> {
+        fn zero() -> Self { 0 }
         fn is_zero(&self) -> bool { *self == 0 }
         fn checked_sub(self, other: T) -> Option<Self> {
             self.checked_sub(Self::from(other))
         }
+        fn checked_add(self, other: T) -> Option<Self> {
+            self.checked_add(Self::from(other))
+        }
     })*
 }}
 impl_total!{ u8, u16, u32, u64, usize }
 
-pub struct SubsetSumIter<It: Copy + 'static>(BoxIter<It>);
-type BoxIter<It> = Box<Iterator<Item=(Vec<It>,Vec<It>)>>;
+// What each subset comes out as.  `chosen` is indexed like the items.
+pub trait Shape<It> {
+    type Item;
+    fn shape(items: &[It], chosen: &[bool]) -> Self::Item;
+}
 
-impl<It: Copy + 'static> SubsetSumIter<It> {
-    // TODO, maybe: template this harder so the unused side can be dropped.
-    // Extend + Default would do it?
-    pub fn new<Tl>(items: &[It], target: Tl) -> Self
-        where Tl: Total<It> + Copy + 'static {
-        SubsetSumIter(subsets_with_sum(Rc::new(items.to_owned()), items.len(), target))
+// The subset and its complement, each in the original order.
+pub struct Both;
+impl<It: Copy> Shape<It> for Both {
+    type Item = (Vec<It>, Vec<It>);
+    fn shape(items: &[It], chosen: &[bool]) -> Self::Item {
+        let mut yes = Vec::new();
+        let mut no = Vec::new();
+        for (&it, &c) in items.iter().zip(chosen) {
+            if c { yes.push(it) } else { no.push(it) }
+        }
+        (yes, no)
     }
 }
 
-impl<It: Copy + 'static> Iterator for SubsetSumIter<It> {
-    type Item = (Vec<It>,Vec<It>);
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+// Just the subset.
+pub struct Chosen;
+impl<It: Copy> Shape<It> for Chosen {
+    type Item = Vec<It>;
+    fn shape(items: &[It], chosen: &[bool]) -> Self::Item {
+        items.iter().zip(chosen).filter(|&(_, &c)| c).map(|(&it, _)| it).collect()
     }
 }
 
-fn subsets_with_sum<It, Tl>(vols: Rc<Vec<It>>, n: usize, target: Tl) -> BoxIter<It>
-    where Tl: Total<It> + Copy + 'static, It: Copy + 'static
-{
-    match n.checked_sub(1) {
-        None => {
-            if target.is_zero() {
-                Box::new(iter::once((vec![], vec![])))
-            } else {
-                Box::new(iter::empty())
-            }
-        },
-        Some(n) => {
-            let voln = vols[n];
-            let without_f = move |vols| {
-                subsets_with_sum(vols, n, target)
-                    .map(move |(yes, mut no)| {
-                        no.push(voln);
-                        (yes, no)
-                    })
-            };
-            let with_f = move |vols, new_target| {
-                subsets_with_sum(vols, n, new_target)
-                    .map(move |(mut yes, no)| {
-                        yes.push(voln);
-                        (yes, no)
-                    })
+// Positions of the subset's items, ascending.
+pub struct Indices;
+impl<It> Shape<It> for Indices {
+    type Item = Vec<usize>;
+    fn shape(_items: &[It], chosen: &[bool]) -> Self::Item {
+        chosen.iter().enumerate().filter(|&(_, &c)| c).map(|(i, _)| i).collect()
+    }
+}
+
+// The order subsets come out in.  `Colex` is by the subset's bitmask
+// read as a number (bit i for item i), which is what falls out of
+// deciding the last item first and trying "without" before "with".
+// `BySize` is fewest items first, and colex within each size.
+// `Size(k)` is only the subsets of exactly `k` items, in colex order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Colex,
+    BySize,
+    Size(usize),
+}
+
+// One decision in progress: `rem` is what's left of the target, and
+// `taken` how many items are in, before deciding this item.
+#[derive(Debug, Clone, Copy)]
+struct Frame<Tl> {
+    rem: Tl,
+    taken: usize,
+    tried: u8,
+}
+
+// Iterates over the subsets of `items` that add up to `target`, with
+// no more state than a stack as deep as the number of items.  Each
+// item counts separately, so equal items give repeated subsets.
+pub struct SubsetSumIter<It, Tl, S = Both> {
+    items: Vec<It>,
+    // sums[i] is the total of items[..i], or None if it overflowed.
+    sums: Vec<Option<Tl>>,
+    target: Tl,
+    order: Order,
+    // With `Order::BySize`, the size currently being enumerated.
+    size: usize,
+    chosen: Vec<bool>,
+    stack: Vec<Frame<Tl>>,
+    shape: PhantomData<S>,
+}
+
+impl<It, Tl> SubsetSumIter<It, Tl, Both>
+    where It: Copy, Tl: Total<It> + Copy + Ord {
+    pub fn new(items: &[It], target: Tl) -> Self {
+        let mut sums = Vec::with_capacity(items.len() + 1);
+        let mut acc = Some(Tl::zero());
+        sums.push(acc);
+        for &it in items {
+            acc = acc.and_then(|a| a.checked_add(it));
+            sums.push(acc);
+        }
+        let mut that = SubsetSumIter {
+            items: items.to_owned(),
+            sums,
+            target,
+            order: Order::Colex,
+            size: 0,
+            chosen: vec![false; items.len()],
+            stack: Vec::with_capacity(items.len() + 1),
+            shape: PhantomData,
+        };
+        that.restart();
+        that
+    }
+}
+
+impl<It, Tl, S> SubsetSumIter<It, Tl, S>
+    where It: Copy, Tl: Total<It> + Copy + Ord, S: Shape<It> {
+    // These are meant to be used before iterating, not partway through.
+    pub fn chosen(self) -> SubsetSumIter<It, Tl, Chosen> {
+        self.reshape()
+    }
+    pub fn indices(self) -> SubsetSumIter<It, Tl, Indices> {
+        self.reshape()
+    }
+    pub fn both(self) -> SubsetSumIter<It, Tl, Both> {
+        self.reshape()
+    }
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self.size = match order {
+            Order::Size(k) => k,
+            _ => 0,
+        };
+        self.restart();
+        self
+    }
+    pub fn by_size(self) -> Self {
+        self.order(Order::BySize)
+    }
+    pub fn of_size(self, k: usize) -> Self {
+        self.order(Order::Size(k))
+    }
+
+    fn reshape<S2>(self) -> SubsetSumIter<It, Tl, S2> {
+        SubsetSumIter {
+            items: self.items,
+            sums: self.sums,
+            target: self.target,
+            order: self.order,
+            size: self.size,
+            chosen: self.chosen,
+            stack: self.stack,
+            shape: PhantomData,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.stack.clear();
+        let root = Frame { rem: self.target, taken: 0, tried: 0 };
+        if self.feasible(0, &root) {
+            self.stack.push(root);
+        }
+    }
+
+    // Whether there could be anything under this frame at this depth:
+    // enough left in the undecided items to make up the remainder, and
+    // (if sizes matter) the right number of them.
+    fn feasible(&self, depth: usize, f: &Frame<Tl>) -> bool {
+        let left = self.items.len() - depth;
+        let enough = match self.sums[left] {
+            None => true,
+            Some(sum) => sum >= f.rem,
+        };
+        let sized = match self.order {
+            Order::Colex => true,
+            Order::BySize | Order::Size(_) =>
+                f.taken <= self.size && f.taken + left >= self.size,
+        };
+        enough && sized
+    }
+
+    fn descend(&mut self, depth: usize, rem: Tl, taken: usize) {
+        let child = Frame { rem, taken, tried: 0 };
+        if self.feasible(depth + 1, &child) {
+            self.stack.push(child);
+        }
+    }
+}
+
+impl<It, Tl, S> Iterator for SubsetSumIter<It, Tl, S>
+    where It: Copy, Tl: Total<It> + Copy + Ord, S: Shape<It> {
+    type Item = S::Item;
+    fn next(&mut self) -> Option<S::Item> {
+        let n = self.items.len();
+        loop {
+            let depth = match self.stack.len() {
+                0 => {
+                    if self.order == Order::BySize && self.size < n {
+                        self.size += 1;
+                        self.restart();
+                        continue;
+                    }
+                    return None;
+                }
+                len => len - 1,
             };
-            match target.checked_sub(voln) {
-                None =>
-                    Box::new(LazyIter::new(move || {
-                        without_f(vols)
-                    })) as BoxIter<It>,
-                Some(new_target) =>
-                    Box::new(LazyIter::new(move || {
-                        let vols_c = vols.clone();
-                        without_f(vols).chain(with_f(vols_c, new_target))
-                    })) as BoxIter<It>,
+            if depth == n {
+                // Feasibility at the bottom means the target was hit.
+                let _leaf = self.stack.pop();
+                return Some(S::shape(&self.items, &self.chosen));
+            }
+            let i = n - 1 - depth;
+            let top = self.stack[depth];
+            self.stack[depth].tried += 1;
+            match top.tried {
+                0 => {
+                    self.chosen[i] = false;
+                    self.descend(depth, top.rem, top.taken);
+                }
+                1 => {
+                    if let Some(rem) = top.rem.checked_sub(self.items[i]) {
+                        self.chosen[i] = true;
+                        self.descend(depth, rem, top.taken + 1);
+                    }
+                }
+                _ => {
+                    let _done = self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+// For when only the number of subsets matters: ways[k] is how many
+// subsets of exactly `k` items add up to `target`.  This is the usual
+// dynamic programming over (size, sum), so it's polynomial in the
+// number of items and the size of the target, rather than exponential.
+pub fn count_by_size<It>(items: &[It], target: It) -> Vec<u64>
+    where It: Copy + Into<u64> {
+    let target = target.into() as usize;
+    table(items, target).into_iter().map(|row| row[target]).collect()
+}
+
+// The same table, the other way: ways[s] is how many subsets of any
+// size add up to `s`, for every `s` up to `limit`.
+pub fn count_by_sum<It>(items: &[It], limit: usize) -> Vec<u64>
+    where It: Copy + Into<u64> {
+    let table = table(items, limit);
+    (0..limit + 1).map(|s| table.iter().map(|row| row[s]).sum()).collect()
+}
+
+// table[k][s]: subsets with k items summing to s, for every s up to
+// `target`.
+fn table<It>(items: &[It], target: usize) -> Vec<Vec<u64>>
+    where It: Copy + Into<u64> {
+    let n = items.len();
+    let mut table = vec![vec![0u64; target + 1]; n + 1];
+    table[0][0] = 1;
+    for (seen, &it) in items.iter().enumerate() {
+        let w = it.into() as usize;
+        if w > target {
+            continue;
+        }
+        for k in (0..seen + 1).rev() {
+            let (lower, upper) = table.split_at_mut(k + 1);
+            let (from, to) = (&lower[k], &mut upper[0]);
+            for s in (w..target + 1).rev() {
+                to[s] += from[s - w];
             }
         }
     }
+    table
 }
 
-// FIXME: needs some unit tests.  day17 has one.
+pub fn count_subsets<It>(items: &[It], target: It) -> u64
+    where It: Copy + Into<u64> {
+    count_by_size(items, target).into_iter().sum()
+}
+
+pub fn smallest_size<It>(items: &[It], target: It) -> Option<usize>
+    where It: Copy + Into<u64> {
+    count_by_size(items, target).into_iter().position(|ways| ways > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+    use self::rand::{Rng,SeedableRng};
+    use super::{SubsetSumIter, Order, count_by_size, count_by_sum, count_subsets, smallest_size};
+
+    type Rand = self::rand::XorShiftRng;
+
+    #[test]
+    fn empty() {
+        let none: &[u8] = &[];
+        assert_eq!(SubsetSumIter::new(none, 0u8).collect::<Vec<_>>(), vec![(vec![], vec![])]);
+        assert_eq!(SubsetSumIter::new(none, 1u8).count(), 0);
+    }
+
+    #[test]
+    fn zero_target() {
+        assert_eq!(SubsetSumIter::new(&[3u8, 4], 0u8).collect::<Vec<_>>(),
+                   vec![(vec![], vec![3, 4])]);
+    }
+
+    #[test]
+    fn colex() {
+        // The same order day17's example comes out in.
+        let subsets: Vec<_> = SubsetSumIter::new(&[20u16, 15, 10, 5, 5], 25u16).chosen().collect();
+        assert_eq!(subsets, vec![vec![15, 10], vec![20, 5], vec![20, 5], vec![15, 5, 5]]);
+    }
+
+    #[test]
+    fn both_halves() {
+        let splits: Vec<_> = SubsetSumIter::new(&[1u8, 2, 3], 3u8).collect();
+        assert_eq!(splits, vec![(vec![1, 2], vec![3]), (vec![3], vec![1, 2])]);
+    }
+
+    #[test]
+    fn indices() {
+        let idxs: Vec<_> = SubsetSumIter::new(&[20u16, 15, 10, 5, 5], 25u16).indices().collect();
+        assert_eq!(idxs, vec![vec![1, 2], vec![0, 3], vec![0, 4], vec![1, 3, 4]]);
+    }
+
+    #[test]
+    fn by_size() {
+        let subsets: Vec<_> = SubsetSumIter::new(&[5u16, 5, 15, 10, 20], 25u16)
+            .by_size().chosen().collect();
+        assert_eq!(subsets, vec![vec![15, 10], vec![5, 20], vec![5, 20], vec![5, 5, 15]]);
+    }
+
+    #[test]
+    fn of_size() {
+        let items = [20u16, 15, 10, 5, 5];
+        let twos: Vec<_> = SubsetSumIter::new(&items, 25u16).of_size(2).chosen().collect();
+        assert_eq!(twos, vec![vec![15, 10], vec![20, 5], vec![20, 5]]);
+        assert_eq!(SubsetSumIter::new(&items, 25u16).of_size(3).count(), 1);
+        assert_eq!(SubsetSumIter::new(&items, 25u16).of_size(1).count(), 0);
+        assert_eq!(SubsetSumIter::new(&items, 25u16).of_size(9).count(), 0);
+    }
+
+    #[test]
+    fn counting() {
+        let items = [20u16, 15, 10, 5, 5];
+        assert_eq!(count_by_size(&items, 25), vec![0, 0, 3, 1, 0, 0]);
+        assert_eq!(count_subsets(&items, 25), 4);
+        assert_eq!(smallest_size(&items, 25), Some(2));
+        assert_eq!(smallest_size(&items, 56), None);
+        assert_eq!(count_subsets(&items, 0), 1);
+        assert_eq!(count_by_size(&[] as &[u8], 0), vec![1]);
+        assert_eq!(count_by_sum(&[4u8, 3, 2, 1, 1], 11), vec![1, 2, 2, 3, 4, 4, 4, 4, 3, 2, 2, 1]);
+        assert_eq!(count_by_sum(&items, 25)[25], 4);
+    }
 
+    #[test]
+    fn overflow() {
+        // The prefix sums overflow u8, which just turns off pruning.
+        let subsets: Vec<_> = SubsetSumIter::new(&[200u8, 100, 50, 50], 200u8).chosen().collect();
+        assert_eq!(subsets, vec![vec![200], vec![100, 50, 50]]);
+    }
+
+    #[test]
+    fn wider_total() {
+        let n = SubsetSumIter::new(&[200u8, 200, 200], 400u16).count();
+        assert_eq!(n, 3);
+    }
+
+    fn brute_force(items: &[u8], target: u32) -> Vec<Vec<usize>> {
+        let n = items.len();
+        (0..1u32 << n).filter(|mask| {
+            (0..n).filter(|&i| mask & 1 << i != 0).map(|i| items[i] as u32).sum::<u32>() == target
+        }).map(|mask| {
+            (0..n).filter(|&i| mask & 1 << i != 0).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn randomly() {
+        let mut rng = Rand::from_seed([17, 17, 17, 17]);
+        for _ in 0..300 {
+            let n = rng.gen_range(0, 13);
+            let items: Vec<u8> = (0..n).map(|_| rng.gen_range(0, 20)).collect();
+            let total = items.iter().map(|&i| i as u32).sum::<u32>();
+            let target = rng.gen_range(0, total + 2);
+            let expected = brute_force(&items, target);
+
+            let colex: Vec<_> = SubsetSumIter::new(&items, target).indices().collect();
+            assert_eq!(colex, expected, "items={:?} target={}", items, target);
+
+            let mut by_size = expected.clone();
+            by_size.sort_by_key(|s| s.len());
+            let sized: Vec<_> = SubsetSumIter::new(&items, target)
+                .order(Order::BySize).indices().collect();
+            assert_eq!(sized, by_size, "items={:?} target={}", items, target);
+
+            if target <= 255 {
+                let counts = count_by_size(&items, target as u8);
+                assert_eq!(counts.len(), n + 1);
+                for (k, &ways) in counts.iter().enumerate() {
+                    assert_eq!(ways as usize, by_size.iter().filter(|s| s.len() == k).count());
+                    let just_k: Vec<_> = SubsetSumIter::new(&items, target).of_size(k)
+                        .indices().collect();
+                    assert_eq!(just_k.len() as u64, ways);
+                }
+            }
+
+            for ((yes, no), idxs) in SubsetSumIter::new(&items, target).zip(expected) {
+                assert_eq!(yes, idxs.iter().map(|&i| items[i]).collect::<Vec<_>>());
+                assert_eq!(yes.len() + no.len(), n);
+            }
+        }
+    }
+}
@@ -1,34 +1,116 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Deref, Index, IndexMut};
+use std::slice;
+use std::sync::Arc;
 
-pub type Id = usize;
+// What a table hands out.  Plain `usize` is the untagged default;
+// `Id<Tag>` keeps ids from different tables from being mixed up.
+pub trait SymId: Copy + Eq + Ord + Hash + fmt::Debug {
+    fn from_index(n: usize) -> Self;
+    fn index(self) -> usize;
+}
+
+pub type Untagged = usize;
+impl SymId for usize {
+    fn from_index(n: usize) -> usize { n }
+    fn index(self) -> usize { self }
+}
+
+// The `fn() -> Tag` is so that `Tag` can be any type at all (usually
+// an uninhabited `enum`) without affecting `Send`/`Sync` or the like.
+pub struct Id<Tag>(usize, PhantomData<fn() -> Tag>);
+impl<Tag> Id<Tag> {
+    pub fn from_index(n: usize) -> Self { Id(n, PhantomData) }
+    pub fn index(self) -> usize { self.0 }
+}
+impl<Tag> SymId for Id<Tag> {
+    fn from_index(n: usize) -> Self { Id::from_index(n) }
+    fn index(self) -> usize { self.0 }
+}
+
+// Everything `derive` would do if it didn't insist on `Tag: Trait` too.
+impl<Tag> Clone for Id<Tag> {
+    fn clone(&self) -> Self { *self }
+}
+impl<Tag> Copy for Id<Tag> { }
+impl<Tag> PartialEq for Id<Tag> {
+    fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+}
+impl<Tag> Eq for Id<Tag> { }
+impl<Tag> PartialOrd for Id<Tag> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl<Tag> Ord for Id<Tag> {
+    fn cmp(&self, other: &Self) -> Ordering { self.0.cmp(&other.0) }
+}
+impl<Tag> Hash for Id<Tag> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.0.hash(state) }
+}
+impl<Tag> fmt::Debug for Id<Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Id({})", self.0)
+    }
+}
+
+impl<T, Tag> Index<Id<Tag>> for [T] {
+    type Output = T;
+    fn index(&self, id: Id<Tag>) -> &T { &self[id.0] }
+}
+impl<T, Tag> IndexMut<Id<Tag>> for [T] {
+    fn index_mut(&mut self, id: Id<Tag>) -> &mut T { &mut self[id.0] }
+}
+impl<T, Tag> Index<Id<Tag>> for Vec<T> {
+    type Output = T;
+    fn index(&self, id: Id<Tag>) -> &T { &self[id.0] }
+}
+impl<T, Tag> IndexMut<Id<Tag>> for Vec<T> {
+    fn index_mut(&mut self, id: Id<Tag>) -> &mut T { &mut self[id.0] }
+}
 
 #[derive(Debug, Clone)]
-pub struct SymTab {
-    rname: HashMap<String, Id>,
+pub struct SymTab<I: SymId = Untagged> {
+    rname: HashMap<String, I>,
     pname: Vec<String>,
 }
 impl SymTab {
-    pub fn new() -> SymTab { SymTab {
-        rname: HashMap::new(),
-        pname: Vec::new(),
-    }}
-    pub fn try_read(&self, s: &str) -> Option<Id> {
+    pub fn new() -> SymTab {
+        Self::default()
+    }
+}
+impl<I: SymId> SymTab<I> {
+    pub fn try_read(&self, s: &str) -> Option<I> {
         self.rname.get(s).map(|&id| id)
     }
-    pub fn read(&mut self, s: &str) -> Id {
-        // This clones the string unnecessarily if it's already
-        // present.  Using `contains_key` and `insert` unecessarily
-        // hashes the string twice if it's not.  Such is life.
-        let entry = self.rname.entry(s.to_owned());
-        let pname = &mut self.pname;
-        *entry.or_insert_with(|| {
-            let n = pname.len();
-            pname.push(s.to_owned());
-            n
-        })
-    }
-    pub fn print(&self, n: Id) -> String {
-        self.pname[n].clone()
+    pub fn read(&mut self, s: &str) -> I {
+        // This hashes the string twice if it's not already present,
+        // but that's cheaper than cloning it every time it is.
+        match self.try_read(s) {
+            Some(id) => id,
+            None => self.insert(s.to_owned()),
+        }
+    }
+    pub fn read_owned(&mut self, s: String) -> I {
+        match self.try_read(&s) {
+            Some(id) => id,
+            None => self.insert(s),
+        }
+    }
+    fn insert(&mut self, s: String) -> I {
+        let n = I::from_index(self.pname.len());
+        self.pname.push(s.clone());
+        self.rname.insert(s, n);
+        n
+    }
+    pub fn name(&self, n: I) -> &str {
+        &self.pname[n.index()]
+    }
+    pub fn print(&self, n: I) -> String {
+        self.pname[n.index()].clone()
     }
     pub fn pborrow(&self) -> &[String] {
         &self.pname
@@ -36,11 +118,97 @@ impl SymTab {
     pub fn len(&self) -> usize {
         self.pname.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.pname.is_empty()
+    }
+    pub fn iter(&self) -> Iter<'_, I> {
+        Iter(self.pname.iter().enumerate(), PhantomData)
+    }
+    // Like `Vec::swap_remove`: the last symbol takes over the removed
+    // one's id, so anything holding that id needs to be told.
+    pub fn remove(&mut self, s: &str) -> Option<I> {
+        let n = self.rname.remove(s)?;
+        let _s = self.pname.swap_remove(n.index());
+        if let Some(moved) = self.pname.get(n.index()) {
+            *self.rname.get_mut(moved as &str).unwrap() = n;
+        }
+        Some(n)
+    }
+    // Adds everything in `other`; the result maps `other`'s ids to ours.
+    pub fn merge<J: SymId>(&mut self, other: &SymTab<J>) -> Box<[I]> {
+        other.pname.iter().map(|s| self.read(s)).collect::<Vec<_>>().into_boxed_slice()
+    }
+    pub fn freeze(self) -> Frozen<I> {
+        Frozen(Arc::new(self))
+    }
+}
+
+// Tagged tables start out from here, since `new` is only for the
+// untagged kind (which keeps `SymTab::new()` from being ambiguous).
+impl<I: SymId> Default for SymTab<I> {
+    fn default() -> Self { SymTab {
+        rname: HashMap::new(),
+        pname: Vec::new(),
+    }}
+}
+
+impl<I: SymId> Index<I> for SymTab<I> {
+    type Output = str;
+    fn index(&self, n: I) -> &str {
+        self.name(n)
+    }
+}
+
+impl<'s, I: SymId> FromIterator<&'s str> for SymTab<I> {
+    fn from_iter<It: IntoIterator<Item = &'s str>>(iter: It) -> Self {
+        let mut stab = SymTab::default();
+        for s in iter {
+            let _id = stab.read(s);
+        }
+        stab
+    }
+}
+
+impl<'t, I: SymId> IntoIterator for &'t SymTab<I> {
+    type Item = (I, &'t str);
+    type IntoIter = Iter<'t, I>;
+    fn into_iter(self) -> Iter<'t, I> {
+        self.iter()
+    }
+}
+
+pub struct Iter<'t, I>(::std::iter::Enumerate<slice::Iter<'t, String>>, PhantomData<I>);
+impl<'t, I: SymId> Iterator for Iter<'t, I> {
+    type Item = (I, &'t str);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(n, s)| (I::from_index(n), s as &str))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+// A table that's done growing.  Clones are cheap and share the same
+// storage, so it can be handed out to as many threads as want it.
+#[derive(Debug, Clone)]
+pub struct Frozen<I: SymId = Untagged>(Arc<SymTab<I>>);
+impl<I: SymId> Frozen<I> {
+    // Gets a mutable table back, copying only if it's still shared.
+    pub fn thaw(self) -> SymTab<I> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|arc| (*arc).clone())
+    }
+}
+
+impl<I: SymId> Deref for Frozen<I> {
+    type Target = SymTab<I>;
+    fn deref(&self) -> &SymTab<I> {
+        &self.0
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SymTab;
+    use super::{SymTab, Id};
 
     #[test]
     fn repeated_read() {
@@ -83,4 +251,106 @@ mod tests {
         let _ = st.read("Law");
         assert_eq!(st.pborrow(), &vec!["Word".to_owned(), "Law".to_owned()] as &[String]);
     }
+
+    #[test]
+    fn name() {
+        let mut st = SymTab::new();
+        let c0 = st.read("Word");
+        let n0 = st.read_owned("Law".to_owned());
+        assert_eq!(st.name(c0), "Word");
+        assert_eq!(&st[n0], "Law");
+        assert_eq!(st.read_owned("Word".to_owned()), c0);
+    }
+
+    #[test]
+    fn iter() {
+        let st: SymTab = "Word Law Word Order".split(' ').collect();
+        assert_eq!(st.len(), 3);
+        assert_eq!(st.iter().collect::<Vec<_>>(), vec![(0, "Word"), (1, "Law"), (2, "Order")]);
+        let mut n = 0;
+        for (id, s) in &st {
+            assert_eq!(st.try_read(s), Some(id));
+            n += 1;
+        }
+        assert_eq!(n, 3);
+    }
+
+    #[test]
+    fn remove() {
+        let mut st: SymTab = "Word Law Order".split(' ').collect();
+        assert_eq!(st.remove("Chaos"), None);
+        assert_eq!(st.remove("Word"), Some(0));
+        assert_eq!(st.len(), 2);
+        assert_eq!(st.try_read("Word"), None);
+        assert_eq!(st.try_read("Order"), Some(0));
+        assert_eq!(st.name(1), "Law");
+        assert_eq!(st.remove("Law"), Some(1));
+        assert_eq!(st.try_read("Order"), Some(0));
+        assert_eq!(st.read("Word"), 1);
+    }
+
+    #[test]
+    fn merge() {
+        let mut st: SymTab = "Word Law".split(' ').collect();
+        let other: SymTab = "Order Word Chaos".split(' ').collect();
+        let remap = st.merge(&other);
+        assert_eq!(&remap as &[usize], &[2, 0, 3]);
+        for (id, s) in &other {
+            assert_eq!(st.name(remap[id]), s);
+        }
+        assert_eq!(st.len(), 4);
+    }
+
+    #[test]
+    fn frozen() {
+        use std::thread;
+        let st: SymTab = "Word Law".split(' ').collect();
+        let fr = st.freeze();
+        let fr2 = fr.clone();
+        let law = thread::spawn(move || fr2.try_read("Law")).join().unwrap();
+        assert_eq!(law, Some(1));
+        assert_eq!(fr.name(0), "Word");
+        let mut st = fr.thaw();
+        assert_eq!(st.read("Order"), 2);
+    }
+
+    enum City {}
+    enum Guest {}
+
+    #[test]
+    fn tagged() {
+        let mut cities: SymTab<Id<City>> = SymTab::default();
+        let mut guests: SymTab<Id<Guest>> = SymTab::default();
+        let london = cities.read("London");
+        let dublin = cities.read("Dublin");
+        let alice = guests.read("Alice");
+        assert!(london < dublin);
+        assert_eq!(cities.read("London"), london);
+        assert_eq!(alice.index(), 0);
+        assert_eq!(&cities[dublin], "Dublin");
+        assert_eq!(guests.name(alice), "Alice");
+        assert_eq!(format!("{:?}", dublin), "Id(1)");
+        let dists = vec![0, 464];
+        assert_eq!(dists[dublin], 464);
+        assert_eq!((&dists as &[_])[london], 0);
+    }
+
+    #[test]
+    fn tagged_hash() {
+        use std::collections::HashSet;
+        let cities: SymTab<Id<City>> = "London Dublin Belfast".split(' ').collect();
+        let set: HashSet<_> = cities.iter().map(|(id, _)| id).collect();
+        assert_eq!(set.len(), 3);
+        let frozen = cities.freeze();
+        assert_eq!(frozen.try_read("Belfast"), Some(Id::from_index(2)));
+    }
+
+    #[test]
+    fn merge_tagged() {
+        let mut cities: SymTab<Id<City>> = "London Dublin".split(' ').collect();
+        let other: SymTab = "Dublin Belfast".split(' ').collect();
+        let remap = cities.merge(&other);
+        assert_eq!(&cities[remap[1]], "Belfast");
+        assert_eq!(remap[0], cities.read("Dublin"));
+    }
 }
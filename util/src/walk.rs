@@ -0,0 +1,185 @@
+use std::convert::TryFrom;
+
+use stack_set::{BitSet, StackSet};
+
+// Walks the tree of partial permutations of `0..n` depth-first, in
+// lexicographic order, yielding each path on the way down.  This is
+// what recursing with `StackSet::push` does, but with the stack kept
+// here instead, so the caller can stop whenever it likes, save the
+// current path and pick up again later with `resume`, or hand out
+// subtrees with `within`.
+//
+// `keep` is asked about each path before it's yielded; if it says no,
+// neither that path nor anything under it is visited.  For pruning
+// based on something the predicate can't see, `prune` does the same
+// for the path that was just yielded.
+pub struct Walk<B: BitSet, P> {
+    set: StackSet<B>,
+    n: usize,
+    // The next thing to try appending at each depth; always one
+    // longer than the current path.
+    cursor: Vec<usize>,
+    // Never backtrack to a path shorter than this.
+    floor: usize,
+    keep: P,
+}
+
+impl<B, P> Walk<B, P>
+    where B: BitSet,
+          B::Idx: TryFrom<usize> + Into<usize>,
+          P: FnMut(&[B::Idx]) -> bool {
+    pub fn new(n: usize, keep: P) -> Option<Self> {
+        Self::start(n, &[], 0, keep)
+    }
+    // Continues a walk as if `prefix` were the last path it yielded.
+    pub fn resume(n: usize, prefix: &[B::Idx], keep: P) -> Option<Self> {
+        Self::start(n, prefix, 0, keep)
+    }
+    // Walks only the paths that strictly extend `prefix`.
+    pub fn within(n: usize, prefix: &[B::Idx], keep: P) -> Option<Self> {
+        Self::start(n, prefix, prefix.len(), keep)
+    }
+    fn start(n: usize, prefix: &[B::Idx], floor: usize, keep: P) -> Option<Self> {
+        let mut set = StackSet::new(n)?;
+        let mut cursor = Vec::with_capacity(n + 1);
+        for &i in prefix {
+            if i.into() >= n || !set.try_push(i) {
+                return None;
+            }
+            cursor.push(i.into() + 1);
+        }
+        cursor.push(0);
+        Some(Walk { set, n, cursor, floor, keep })
+    }
+    pub fn path(&self) -> &[B::Idx] {
+        &self.set
+    }
+    // Skips everything under the current path.
+    pub fn prune(&mut self) {
+        let d = self.set.len();
+        self.cursor[d] = self.n;
+    }
+}
+
+impl<B, P> Iterator for Walk<B, P>
+    where B: BitSet,
+          B::Idx: TryFrom<usize> + Into<usize>,
+          P: FnMut(&[B::Idx]) -> bool {
+    type Item = Vec<B::Idx>;
+    fn next(&mut self) -> Option<Vec<B::Idx>> {
+        loop {
+            let d = self.set.len();
+            if self.cursor[d] < self.n {
+                let i = self.cursor[d];
+                self.cursor[d] += 1;
+                let i = B::Idx::try_from(i).ok().expect("index out of range for BitSet::Idx");
+                if self.set.try_push(i) {
+                    if (self.keep)(&self.set) {
+                        self.cursor.push(0);
+                        return Some(self.set.to_vec());
+                    }
+                    let _i = self.set.pop();
+                }
+            } else if d <= self.floor {
+                return None;
+            } else {
+                let _i = self.set.pop();
+                let _c = self.cursor.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Walk;
+
+    type W<P> = Walk<u32, P>;
+
+    fn all(_: &[u8]) -> bool { true }
+
+    #[test]
+    fn empty() {
+        let mut w = W::new(0, all).unwrap();
+        assert_eq!(w.next(), None);
+        assert_eq!(w.next(), None);
+    }
+
+    #[test]
+    fn order() {
+        let paths: Vec<_> = W::new(3, all).unwrap().collect();
+        assert_eq!(paths, vec![vec![0], vec![0, 1], vec![0, 1, 2], vec![0, 2], vec![0, 2, 1],
+                               vec![1], vec![1, 0], vec![1, 0, 2], vec![1, 2], vec![1, 2, 0],
+                               vec![2], vec![2, 0], vec![2, 0, 1], vec![2, 1], vec![2, 1, 0]]);
+    }
+
+    #[test]
+    fn count() {
+        let full = W::new(6, all).unwrap().filter(|p| p.len() == 6).count();
+        assert_eq!(full, 720);
+        let wide = Walk::<Vec<u64>, _>::new(70, |p: &[usize]| p.len() <= 2).unwrap()
+            .filter(|p| p.len() == 2).count();
+        assert_eq!(wide, 70 * 69);
+    }
+
+    #[test]
+    fn keep() {
+        // Only increasing sequences.
+        let paths: Vec<_> = W::new(3, |p: &[u8]| p.windows(2).all(|w| w[0] < w[1]))
+            .unwrap().collect();
+        assert_eq!(paths, vec![vec![0], vec![0, 1], vec![0, 1, 2], vec![0, 2],
+                               vec![1], vec![1, 2], vec![2]]);
+    }
+
+    #[test]
+    fn prune() {
+        let mut w = W::new(3, all).unwrap();
+        let mut paths = vec![];
+        while let Some(p) = w.next() {
+            if p == vec![1] {
+                w.prune();
+            }
+            paths.push(p);
+        }
+        assert_eq!(paths.len(), 15 - 4);
+        assert!(paths.contains(&vec![1]));
+        assert!(!paths.contains(&vec![1, 0]));
+    }
+
+    #[test]
+    fn early_exit() {
+        let mut w = W::new(10, all).unwrap();
+        let first_full = w.by_ref().find(|p| p.len() == 10).unwrap();
+        assert_eq!(first_full, (0..10).collect::<Vec<_>>());
+        assert_eq!(w.path(), &first_full as &[u8]);
+        assert_eq!(w.next(), Some(vec![0, 1, 2, 3, 4, 5, 6, 7, 9]));
+    }
+
+    #[test]
+    fn resume() {
+        let everything: Vec<_> = W::new(4, all).unwrap().collect();
+        for (k, saved) in everything.iter().enumerate() {
+            let rest: Vec<_> = W::resume(4, saved, all).unwrap().collect();
+            assert_eq!(&rest as &[_], &everything[k + 1..]);
+        }
+    }
+
+    #[test]
+    fn within() {
+        let mut split = vec![];
+        for first in 0..4 {
+            split.push(vec![first]);
+            split.extend(W::within(4, &[first], all).unwrap());
+        }
+        let everything: Vec<_> = W::new(4, all).unwrap().collect();
+        assert_eq!(split, everything);
+        assert_eq!(W::within(4, &[2, 0, 3, 1], all).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn bad_prefix() {
+        assert!(W::resume(4, &[1, 1], all).is_none());
+        assert!(W::within(4, &[4], all).is_none());
+        assert!(W::new(33, all).is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::ops::Deref;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,6 +16,51 @@ impl<T> WriteOnce<T> {
     }
 }
 
+// What `set_with` does about a second write.
+#[derive(Debug, Clone, Copy)]
+pub enum Policy<T> {
+    Reject,
+    AcceptEqual,
+    KeepFirst,
+    KeepLast,
+    Combine(fn(T, T) -> T),
+}
+
+// Both sides of a rejected write; the old value is still there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict<T> {
+    pub old: T,
+    pub new: T,
+}
+impl<T: fmt::Display> fmt::Display for Conflict<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} vs {}", self.old, self.new)
+    }
+}
+
+impl<T: Clone + PartialEq> WriteOnce<T> {
+    pub fn set_with(&mut self, val: T, policy: Policy<T>) -> Result<(), Conflict<T>> {
+        let old = match self.0.take() {
+            None => {
+                self.0 = Some(val);
+                return Ok(());
+            }
+            Some(old) => old
+        };
+        let (keep, rv) = match policy {
+            Policy::AcceptEqual if old == val => (old, Ok(())),
+            Policy::Reject | Policy::AcceptEqual => {
+                (old.clone(), Err(Conflict { old, new: val }))
+            }
+            Policy::KeepFirst => (old, Ok(())),
+            Policy::KeepLast => (val, Ok(())),
+            Policy::Combine(f) => (f(old, val), Ok(())),
+        };
+        self.0 = Some(keep);
+        rv
+    }
+}
+
 impl<T> Default for WriteOnce<T> {
     fn default() -> Self {
         Self::new()
@@ -36,7 +82,7 @@ impl<T> IntoIterator for WriteOnce<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::WriteOnce;
+    use super::{WriteOnce, Policy, Conflict};
 
     #[test]
     fn get_and_set() {
@@ -76,4 +122,40 @@ mod tests {
         wo.set(17).unwrap();
         assert_eq!(wo.into_iter().collect::<Vec<_>>(), vec![17]);
     }
+
+    fn twice(a: i32, b: i32, policy: Policy<i32>) -> (Result<(), Conflict<i32>>, Option<i32>) {
+        let mut wo = WriteOnce::new();
+        assert_eq!(wo.set_with(a, policy), Ok(()));
+        let r = wo.set_with(b, policy);
+        (r, wo.into_inner())
+    }
+
+    #[test]
+    fn reject() {
+        assert_eq!(twice(1, 2, Policy::Reject), (Err(Conflict { old: 1, new: 2 }), Some(1)));
+        assert_eq!(twice(1, 1, Policy::Reject), (Err(Conflict { old: 1, new: 1 }), Some(1)));
+    }
+
+    #[test]
+    fn accept_equal() {
+        assert_eq!(twice(1, 2, Policy::AcceptEqual), (Err(Conflict { old: 1, new: 2 }), Some(1)));
+        assert_eq!(twice(1, 1, Policy::AcceptEqual), (Ok(()), Some(1)));
+    }
+
+    #[test]
+    fn keep() {
+        assert_eq!(twice(1, 2, Policy::KeepFirst), (Ok(()), Some(1)));
+        assert_eq!(twice(1, 2, Policy::KeepLast), (Ok(()), Some(2)));
+    }
+
+    #[test]
+    fn combine() {
+        assert_eq!(twice(1, 2, Policy::Combine(|a, b| a + b)), (Ok(()), Some(3)));
+        assert_eq!(twice(5, 2, Policy::Combine(::std::cmp::min)), (Ok(()), Some(2)));
+    }
+
+    #[test]
+    fn conflict_display() {
+        assert_eq!(Conflict { old: 464, new: 465 }.to_string(), "464 vs 465");
+    }
 }